@@ -38,6 +38,7 @@ buildargs=(
     "-p nostr --no-default-features --features alloc,all-nips"    # alloc + all-nips
     "-p nostr --all-features"                                     # All features
     "-p nostr-database"
+    "-p nostr-database --features snapshot"
     "-p nostr-lmdb"
     "-p nostr-mls-storage"
     "-p nostr-mls-memory-storage"
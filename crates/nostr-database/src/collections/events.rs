@@ -4,9 +4,11 @@
 
 use std::collections::btree_set::IntoIter;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
-use nostr::{Event, Filter};
+use nostr::nips::nip51::MuteList;
+use nostr::{Event, EventId, Filter, PublicKey};
 
 use super::tree::{BTreeCappedSet, Capacity, OverCapacityPolicy};
 
@@ -21,6 +23,25 @@ pub struct Events {
     prev_not_match: bool,
 }
 
+/// Difference between two [`Events`] collections
+///
+/// See [`Events::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventsDiff {
+    /// Events not in the previous collection (descending order)
+    pub added: Vec<Event>,
+    /// Events no longer in the collection (descending order)
+    pub removed: Vec<Event>,
+}
+
+impl EventsDiff {
+    /// Check if the collections have the same events
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
 impl PartialEq for Events {
     fn eq(&self, other: &Self) -> bool {
         self.set == other.set
@@ -61,6 +82,24 @@ impl Events {
         self.set.is_empty()
     }
 
+    /// Check if the collection is bounded
+    ///
+    /// A collection is bounded if was created from a [`Filter`] with a `limit`
+    /// and has not been merged with collections related to other filters (see [`Events::merge`]).
+    #[inline]
+    pub fn is_bounded(&self) -> bool {
+        self.capacity_limit().is_some()
+    }
+
+    /// Get the max number of events the collection can hold, if bounded
+    #[inline]
+    pub fn capacity_limit(&self) -> Option<usize> {
+        match self.set.capacity() {
+            Capacity::Bounded { max, .. } => Some(max),
+            Capacity::Unbounded => None,
+        }
+    }
+
     /// Check if contains [`Event`]
     #[inline]
     pub fn contains(&self, event: &Event) -> bool {
@@ -95,6 +134,17 @@ impl Events {
         self.set.extend(events);
     }
 
+    /// Retain only the events that match the predicate
+    ///
+    /// The order and the capacity of the collection are preserved.
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Event) -> bool,
+    {
+        self.set.retain(f);
+    }
+
     /// Merge events collections into a single one.
     ///
     /// Collection is converted to unbounded if one of the merge [`Events`] have a different hash.
@@ -114,6 +164,177 @@ impl Events {
         self
     }
 
+    /// Merge events collections into a single one, keeping at most `limit` events.
+    ///
+    /// Unlike [`Events::merge`], the `limit` is applied to the merged collection
+    /// regardless of the filters the collections are related to: only the newest `limit` events are kept.
+    ///
+    /// Useful to merge the pages fetched from many relays for the same filter.
+    pub fn merge_bounded(mut self, other: Self, limit: usize) -> Self {
+        if self.hash != other.hash || self.prev_not_match || other.prev_not_match {
+            self.hash = 0;
+            self.prev_not_match = true;
+        }
+
+        // Extend without discarding events and then apply the limit to the merged set
+        self.set.change_capacity(Capacity::Unbounded);
+        self.extend(other.set);
+        self.set.change_capacity(Capacity::Bounded {
+            max: limit,
+            policy: POLICY,
+        });
+
+        self
+    }
+
+    /// Get a new collection with the events that match the predicate
+    ///
+    /// The returned collection is unbounded and the original one is left intact.
+    pub fn filtered<F>(&self, f: F) -> Self
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let mut set: BTreeCappedSet<Event> = BTreeCappedSet::unbounded();
+        set.extend(self.set.iter().filter(|e| f(e)).cloned());
+
+        Self {
+            set,
+            // Not related to the original filter anymore
+            hash: 0,
+            prev_not_match: true,
+        }
+    }
+
+    /// Get a new collection with the events that match the [`Filter`]
+    ///
+    /// The events are matched with the same logic used by the databases (see [`Filter::match_event`]).
+    /// The returned collection keeps the capacity of the original one and,
+    /// if the filter has a `limit`, contains at most `limit` events (the newest ones).
+    pub fn filter(&self, filter: &Filter) -> Self {
+        let mut set: BTreeCappedSet<Event> = BTreeCappedSet::unbounded();
+        set.change_capacity(self.set.capacity());
+
+        // Lookup ID: EVENT_ORD_IMPL
+        let matching = self
+            .set
+            .iter()
+            .filter(|e| filter.match_event(e))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned();
+        set.extend(matching);
+
+        Self {
+            set,
+            hash: self.hash,
+            prev_not_match: self.prev_not_match,
+        }
+    }
+
+    /// Remove the events muted by the [`MuteList`]
+    ///
+    /// An event is muted if it's authored by a muted public key, references a muted event,
+    /// has a muted hashtag or contains a muted word (the hashtags and words are compared case-insensitive).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/51.md>
+    pub fn apply_mute_list(&mut self, mute: &MuteList) {
+        let public_keys: HashSet<&PublicKey> = mute.public_keys.iter().collect();
+        let event_ids: HashSet<&EventId> = mute.event_ids.iter().collect();
+        let hashtags: HashSet<String> = mute.hashtags.iter().map(|h| h.to_lowercase()).collect();
+        let words: Vec<String> = mute.words.iter().map(|w| w.to_lowercase()).collect();
+
+        self.set.retain(|event| {
+            if public_keys.contains(&event.pubkey) {
+                return false;
+            }
+
+            if event.tags.event_ids().any(|id| event_ids.contains(id)) {
+                return false;
+            }
+
+            if !hashtags.is_empty()
+                && event
+                    .tags
+                    .hashtags()
+                    .any(|h| hashtags.contains(&h.to_lowercase()))
+            {
+                return false;
+            }
+
+            if !words.is_empty() {
+                let content: String = event.content.to_lowercase();
+                if words.iter().any(|w| content.contains(w.as_str())) {
+                    return false;
+                }
+            }
+
+            true
+        });
+    }
+
+    /// Compute the events added and removed compared to a `previous` collection
+    ///
+    /// Events are compared by ID.
+    pub fn diff(&self, previous: &Self) -> EventsDiff {
+        let ids: HashSet<&EventId> = self.set.iter().map(|e| &e.id).collect();
+        let previous_ids: HashSet<&EventId> = previous.set.iter().map(|e| &e.id).collect();
+
+        EventsDiff {
+            added: self
+                .set
+                .iter()
+                .filter(|e| !previous_ids.contains(&e.id))
+                .cloned()
+                .collect(),
+            removed: previous
+                .set
+                .iter()
+                .filter(|e| !ids.contains(&e.id))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Check if the collection fully satisfies the [`Filter`]
+    ///
+    /// Useful to decide if a local query is complete or if the events must still be fetched from relays:
+    /// - if the filter has a `limit`, the collection must contain at least `limit` events matching the filter;
+    /// - else, if the filter has a `since`, the oldest event of the collection must be at or before `since`,
+    ///   meaning that the covered time range reaches the `since` cursor.
+    ///
+    /// Without `limit` and `since` the completeness can't be determined, so `false` is returned.
+    pub fn satisfies(&self, filter: &Filter) -> bool {
+        match (filter.limit, filter.since) {
+            (Some(limit), ..) => {
+                let matching: usize = self
+                    .set
+                    .iter()
+                    .filter(|e| filter.match_event(e))
+                    .take(limit)
+                    .count();
+                matching >= limit
+            }
+            (None, Some(since)) => self.last().is_some_and(|e| e.created_at <= since),
+            (None, None) => false,
+        }
+    }
+
+    /// Group the events by the hash of their normalized content
+    ///
+    /// The content is normalized by stripping the URLs and collapsing the whitespaces,
+    /// so the same post published by many authors (i.e. spam campaigns) ends up in the same group.
+    pub fn group_by_content_hash(&self) -> HashMap<u64, Vec<&Event>> {
+        let mut groups: HashMap<u64, Vec<&Event>> = HashMap::new();
+
+        for event in self.set.iter() {
+            groups
+                .entry(normalized_content_hash(&event.content))
+                .or_default()
+                .push(event);
+        }
+
+        groups
+    }
+
     /// Get first [`Event`] (descending order)
     #[inline]
     pub fn first(&self) -> Option<&Event> {
@@ -142,6 +363,22 @@ impl Events {
         self.into_iter().next_back()
     }
 
+    /// Get the newest [`Event`]
+    ///
+    /// Alias of [`Events::first`].
+    #[inline]
+    pub fn newest(&self) -> Option<&Event> {
+        self.first()
+    }
+
+    /// Get the oldest [`Event`]
+    ///
+    /// Alias of [`Events::last`].
+    #[inline]
+    pub fn oldest(&self) -> Option<&Event> {
+        self.last()
+    }
+
     /// Iterate events in descending order
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &Event> {
@@ -149,6 +386,13 @@ impl Events {
         self.set.iter()
     }
 
+    /// Iterate events in ascending order (oldest first)
+    #[inline]
+    pub fn iter_ascending(&self) -> impl Iterator<Item = &Event> {
+        // Lookup ID: EVENT_ORD_IMPL
+        self.set.iter().rev()
+    }
+
     /// Convert the collection to vector of events.
     #[inline]
     pub fn to_vec(self) -> Vec<Event> {
@@ -166,9 +410,26 @@ impl IntoIterator for Events {
     }
 }
 
+fn normalized_content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    // Hashing the words one by one collapses the whitespaces
+    content
+        .split_whitespace()
+        .filter(|word| !is_url(word))
+        .for_each(|word| word.hash(&mut hasher));
+
+    hasher.finish()
+}
+
+#[inline]
+fn is_url(word: &str) -> bool {
+    word.starts_with("http://") || word.starts_with("https://") || word.starts_with("www.")
+}
+
 #[cfg(test)]
 mod tests {
-    use nostr::{JsonUtil, Kind};
+    use nostr::{EventBuilder, JsonUtil, Keys, Kind, Tag, Timestamp};
 
     use super::*;
 
@@ -283,4 +544,341 @@ mod tests {
         assert!(events.prev_not_match);
         assert_eq!(events.set.capacity(), Capacity::Unbounded);
     }
+
+    #[test]
+    fn test_retain() {
+        let keys = Keys::generate();
+        let mut events = Events::new(&Filter::new().limit(10));
+        events.extend((1..=5).map(|i| {
+            EventBuilder::text_note(format!("Event #{i}"))
+                .custom_created_at(Timestamp::from_secs(i * 100))
+                .sign_with_keys(&keys)
+                .unwrap()
+        }));
+
+        let cutoff = Timestamp::from_secs(250);
+        events.retain(|e| e.created_at > cutoff);
+
+        assert_eq!(events.len(), 3);
+        let created_at: Vec<u64> = events.iter().map(|e| e.created_at.as_u64()).collect();
+        assert_eq!(created_at, vec![500, 400, 300]);
+        assert_eq!(events.capacity_limit(), Some(10));
+    }
+
+    #[test]
+    fn test_merge_bounded() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = (1..=6)
+            .map(|i| {
+                EventBuilder::text_note(format!("Event #{i}"))
+                    .custom_created_at(Timestamp::from_secs(i * 100))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        let filter = Filter::new().kind(Kind::TextNote).limit(3);
+
+        let page = |range: std::ops::Range<usize>| {
+            let mut page = Events::new(&filter);
+            page.extend(events[range].iter().cloned());
+            page
+        };
+
+        let created_at = |events: &Events| -> Vec<u64> {
+            events.iter().map(|e| e.created_at.as_u64()).collect()
+        };
+
+        // Overlapping pages
+        let merged = page(0..3).merge_bounded(page(2..5), 4);
+        assert_eq!(created_at(&merged), vec![500, 400, 300, 200]);
+        assert_eq!(merged.capacity_limit(), Some(4));
+
+        // Disjoint pages
+        let merged = page(0..3).merge_bounded(page(3..6), 4);
+        assert_eq!(created_at(&merged), vec![600, 500, 400, 300]);
+        assert_eq!(merged.capacity_limit(), Some(4));
+
+        // Pages of different filters: the limit is applied anyway
+        let mut other = Events::new(&Filter::new().kind(Kind::Metadata).limit(10));
+        other.extend(events[3..6].iter().cloned());
+        let merged = page(0..3).merge_bounded(other, 2);
+        assert_eq!(created_at(&merged), vec![600, 500]);
+        assert_eq!(merged.capacity_limit(), Some(2));
+        assert!(merged.prev_not_match);
+
+        // The limit is kept on next inserts
+        let mut merged = merged;
+        merged.insert(events[0].clone());
+        assert_eq!(created_at(&merged), vec![600, 500]);
+    }
+
+    #[test]
+    fn test_is_bounded() {
+        let events = Events::new(&Filter::new().kind(Kind::TextNote).limit(10));
+        assert!(events.is_bounded());
+        assert_eq!(events.capacity_limit(), Some(10));
+
+        let events = Events::new(&Filter::new().kind(Kind::TextNote));
+        assert!(!events.is_bounded());
+        assert_eq!(events.capacity_limit(), None);
+
+        // Merge collections related to different filters
+        let events1 = Events::new(&Filter::new().kind(Kind::TextNote).limit(10));
+        let events2 = Events::new(&Filter::new().kind(Kind::Metadata).limit(1));
+        let events = events1.merge(events2);
+        assert!(!events.is_bounded());
+        assert_eq!(events.capacity_limit(), None);
+    }
+
+    #[test]
+    fn test_filtered() {
+        let keys = Keys::generate();
+        let note = EventBuilder::text_note("Text note")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let reaction = EventBuilder::new(Kind::Reaction, "+")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let mut events = Events::new(&Filter::new().limit(10));
+        events.insert(note.clone());
+        events.insert(reaction.clone());
+
+        let notes = events.filtered(|e| e.kind == Kind::TextNote);
+        assert_eq!(notes.len(), 1);
+        assert!(notes.contains(&note));
+        assert_eq!(notes.set.capacity(), Capacity::Unbounded);
+
+        // The original collection is unchanged
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&note));
+        assert!(events.contains(&reaction));
+    }
+
+    #[test]
+    fn test_iter_ascending() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = [300, 100, 200]
+            .into_iter()
+            .map(|secs| {
+                EventBuilder::text_note(format!("At {secs}"))
+                    .custom_created_at(Timestamp::from_secs(secs))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        let mut collection = Events::new(&Filter::new());
+        collection.extend(events);
+
+        let ascending: Vec<u64> = collection
+            .iter_ascending()
+            .map(|e| e.created_at.as_u64())
+            .collect();
+        assert_eq!(ascending, vec![100, 200, 300]);
+
+        let descending: Vec<u64> = collection.iter().map(|e| e.created_at.as_u64()).collect();
+        assert_eq!(descending, vec![300, 200, 100]);
+
+        assert_eq!(collection.newest().unwrap().created_at.as_u64(), 300);
+        assert_eq!(collection.oldest().unwrap().created_at.as_u64(), 100);
+
+        let empty = Events::new(&Filter::new());
+        assert!(empty.iter_ascending().next().is_none());
+        assert!(empty.newest().is_none());
+        assert!(empty.oldest().is_none());
+    }
+
+    #[test]
+    fn test_filter() {
+        let keys = Keys::generate();
+        let alice = Keys::generate().public_key();
+        let bob = Keys::generate().public_key();
+
+        let note = EventBuilder::text_note("Text note")
+            .custom_created_at(Timestamp::from_secs(100))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let mention_alice = EventBuilder::text_note("Hi alice")
+            .tag(Tag::public_key(alice))
+            .custom_created_at(Timestamp::from_secs(200))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let mention_bob = EventBuilder::text_note("Hi bob")
+            .tag(Tag::public_key(bob))
+            .custom_created_at(Timestamp::from_secs(300))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let reaction = EventBuilder::new(Kind::Reaction, "+")
+            .tag(Tag::public_key(alice))
+            .custom_created_at(Timestamp::from_secs(400))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let mut events = Events::new(&Filter::new().limit(10));
+        events.extend([
+            note.clone(),
+            mention_alice.clone(),
+            mention_bob.clone(),
+            reaction.clone(),
+        ]);
+
+        // By kind
+        let notes = events.filter(&Filter::new().kind(Kind::TextNote));
+        assert_eq!(
+            notes.iter().collect::<Vec<_>>(),
+            vec![&mention_bob, &mention_alice, &note]
+        );
+        assert_eq!(notes.capacity_limit(), Some(10));
+
+        // By `#p` tag
+        let alice_mentions = events.filter(&Filter::new().pubkey(alice));
+        assert_eq!(
+            alice_mentions.iter().collect::<Vec<_>>(),
+            vec![&reaction, &mention_alice]
+        );
+
+        // By kind and `#p` tag
+        let filtered = events.filter(&Filter::new().kind(Kind::TextNote).pubkey(alice));
+        assert_eq!(filtered.iter().collect::<Vec<_>>(), vec![&mention_alice]);
+
+        // With limit: the newest events are kept
+        let newest = events.filter(&Filter::new().kind(Kind::TextNote).limit(2));
+        assert_eq!(
+            newest.iter().collect::<Vec<_>>(),
+            vec![&mention_bob, &mention_alice]
+        );
+
+        // The original collection is unchanged
+        assert_eq!(events.len(), 4);
+    }
+
+    #[test]
+    fn test_apply_mute_list() {
+        let spammer = Keys::generate();
+        let keys = Keys::generate();
+
+        let spam = EventBuilder::text_note("Buy now")
+            .sign_with_keys(&spammer)
+            .unwrap();
+        let hashtag = EventBuilder::text_note("Another one")
+            .tag(Tag::hashtag("Airdrop"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let reply = EventBuilder::text_note("Reply to spam")
+            .tag(Tag::event(spam.id))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let note = EventBuilder::text_note("Hello")
+            .tag(Tag::hashtag("nostr"))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let mut events = Events::new(&Filter::new());
+        events.extend([spam.clone(), hashtag.clone(), reply.clone(), note.clone()]);
+
+        let mute = MuteList {
+            public_keys: vec![spammer.public_key()],
+            hashtags: vec![String::from("airdrop")],
+            ..Default::default()
+        };
+        events.apply_mute_list(&mute);
+
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&reply));
+        assert!(events.contains(&note));
+
+        let mute = MuteList {
+            event_ids: vec![spam.id],
+            ..Default::default()
+        };
+        events.apply_mute_list(&mute);
+        assert_eq!(events.iter().collect::<Vec<_>>(), vec![&note]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..4)
+            .map(|i| {
+                EventBuilder::text_note(format!("Text note {i}"))
+                    .custom_created_at(Timestamp::from_secs(1_700_000_000 + i))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        let filter = Filter::new().kind(Kind::TextNote);
+
+        let mut previous = Events::new(&filter);
+        previous.extend(events[0..3].iter().cloned());
+
+        let mut current = Events::new(&filter);
+        current.extend(events[1..4].iter().cloned());
+
+        let diff = current.diff(&previous);
+        assert_eq!(diff.added, vec![events[3].clone()]);
+        assert_eq!(diff.removed, vec![events[0].clone()]);
+
+        // Same snapshot
+        assert!(current.diff(&current).is_empty());
+    }
+
+    #[test]
+    fn test_satisfies() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..3)
+            .map(|i| {
+                EventBuilder::text_note(format!("Text note {i}"))
+                    .custom_created_at(Timestamp::from_secs(1_700_000_000 + i))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        let mut cached = Events::new(&Filter::new().kind(Kind::TextNote));
+        cached.extend(events);
+
+        // Satisfied
+        assert!(cached.satisfies(&Filter::new().kind(Kind::TextNote).limit(3)));
+        assert!(cached.satisfies(&Filter::new().since(Timestamp::from_secs(1_700_000_001))));
+
+        // Under-filled
+        assert!(!cached.satisfies(&Filter::new().kind(Kind::TextNote).limit(4)));
+        assert!(!cached.satisfies(&Filter::new().kind(Kind::Metadata).limit(1)));
+        assert!(!cached.satisfies(&Filter::new().since(Timestamp::from_secs(1_600_000_000))));
+
+        // Can't be determined
+        assert!(!cached.satisfies(&Filter::new().kind(Kind::TextNote)));
+    }
+
+    #[test]
+    fn test_group_by_content_hash() {
+        let spam1 = EventBuilder::text_note("Buy   now!\nhttps://spam.example/a")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let spam2 = EventBuilder::text_note("Buy now! https://spam.example/b")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let note = EventBuilder::text_note("Hello world")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+
+        let mut events = Events::new(&Filter::new());
+        events.insert(spam1.clone());
+        events.insert(spam2.clone());
+        events.insert(note.clone());
+
+        let groups = events.group_by_content_hash();
+        assert_eq!(groups.len(), 2);
+
+        let spam: &Vec<&Event> = groups.values().find(|g| g.len() == 2).unwrap();
+        assert!(spam.contains(&&spam1));
+        assert!(spam.contains(&&spam2));
+        assert_ne!(spam1.pubkey, spam2.pubkey);
+
+        let single: &Vec<&Event> = groups.values().find(|g| g.len() == 1).unwrap();
+        assert_eq!(single, &vec![&note]);
+    }
 }
@@ -156,19 +156,17 @@ where
 
     /// Change capacity
     pub fn change_capacity(&mut self, capacity: Capacity) {
-        match capacity {
-            // Bounded capacity and limit reached
-            Capacity::Bounded { max, policy } if self.set.len() > max => {
-                while self.set.len() != max {
-                    match policy {
-                        OverCapacityPolicy::First => self.set.pop_first(),
-                        OverCapacityPolicy::Last => self.set.pop_last(),
-                    };
-                }
+        // Bounded capacity and limit reached
+        if let Capacity::Bounded { max, policy } = capacity {
+            while self.set.len() > max {
+                match policy {
+                    OverCapacityPolicy::First => self.set.pop_first(),
+                    OverCapacityPolicy::Last => self.set.pop_last(),
+                };
             }
-            // Unbounded capacity or bounded capacity not reached
-            _ => self.capacity = capacity,
         }
+
+        self.capacity = capacity;
     }
 
     #[inline]
@@ -286,6 +284,16 @@ where
         self.set.remove(value)
     }
 
+    /// Retain only the values that match the predicate
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        T: Ord,
+        F: FnMut(&T) -> bool,
+    {
+        self.set.retain(f)
+    }
+
     /// Get first value
     #[inline]
     pub fn first(&self) -> Option<&T>
@@ -410,6 +418,25 @@ mod tests {
         // 1 has been discarded due to resize
         assert_eq!(set.len(), 2);
         assert!(!set.remove(&1));
+        assert_eq!(set.capacity(), Capacity::bounded(2));
+    }
+
+    #[test]
+    fn test_change_capacity_keeps_new_capacity() {
+        let mut set = BTreeCappedSet::bounded(3);
+        set.insert(1);
+        set.insert(2);
+        set.insert(3);
+
+        // Shrink, discarding elements
+        set.change_capacity(Capacity::bounded(2));
+
+        // The new capacity must be kept on next inserts
+        set.insert(4);
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.capacity(), Capacity::bounded(2));
+        assert!(set.contains(&4));
+        assert!(set.contains(&3));
     }
 
     #[test]
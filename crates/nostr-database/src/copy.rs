@@ -0,0 +1,224 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Copy events between databases
+
+use std::collections::HashSet;
+
+use nostr::{Event, EventId, Filter, Timestamp};
+
+use crate::{DatabaseError, Events, NostrEventsDatabase, SaveEventStatus};
+
+/// Max number of events loaded into memory at once
+const PAGE_SIZE: usize = 1000;
+
+/// Copy the events that match the [`Filter`] from a database to another one
+///
+/// Useful to migrate from a backend to another one (i.e., from memory to a persistent database).
+/// Use `Filter::new()` to copy all the events.
+///
+/// The events are copied from the newest to the oldest, in pages of bounded size,
+/// so the whole database is never loaded into memory
+/// (only the events sharing the same `created_at` of a full page are loaded together).
+///
+/// Return the number of events saved into the destination database:
+/// the events rejected by the destination (i.e., already stored) aren't counted.
+pub async fn copy_database(
+    from: &dyn NostrEventsDatabase,
+    to: &dyn NostrEventsDatabase,
+    filter: Filter,
+) -> Result<usize, DatabaseError> {
+    copy_database_paged(from, to, filter, PAGE_SIZE).await
+}
+
+async fn copy_database_paged(
+    from: &dyn NostrEventsDatabase,
+    to: &dyn NostrEventsDatabase,
+    filter: Filter,
+    page_size: usize,
+) -> Result<usize, DatabaseError> {
+    let mut remaining: Option<usize> = filter.limit;
+    let mut until: Option<Timestamp> = filter.until;
+    // IDs of the already copied events having `created_at` equal to `until`
+    let mut copied_at_until: HashSet<EventId> = HashSet::new();
+    let mut copied: usize = 0;
+
+    loop {
+        let limit: usize = match remaining {
+            Some(0) => break,
+            Some(remaining) => remaining.min(page_size),
+            None => page_size,
+        };
+
+        let mut page_filter: Filter = filter.clone();
+        page_filter.until = until;
+        page_filter.limit = Some(limit);
+
+        let page: Events = from.query(page_filter).await?;
+        let fetched: usize = page.len();
+        let (newest, oldest) = match (page.newest(), page.oldest()) {
+            (Some(newest), Some(oldest)) => (newest.created_at, oldest.created_at),
+            _ => break,
+        };
+
+        // The page is full and all its events have the same `created_at`:
+        // the page limit can't split them, so copy all the events at that timestamp.
+        let whole_timestamp: bool = fetched == limit && newest == oldest;
+
+        let events: Vec<Event> = if whole_timestamp {
+            let mut same_timestamp: Filter = filter.clone();
+            same_timestamp.since = Some(oldest);
+            same_timestamp.until = Some(oldest);
+            same_timestamp.limit = None;
+
+            from.query(same_timestamp)
+                .await?
+                .into_iter()
+                .filter(|e| !copied_at_until.contains(&e.id))
+                .take(remaining.unwrap_or(usize::MAX))
+                .collect()
+        } else {
+            page.into_iter()
+                .filter(|e| !copied_at_until.contains(&e.id))
+                .collect()
+        };
+
+        copied += save_page(to, &events).await?;
+
+        if let Some(remaining) = remaining.as_mut() {
+            *remaining = remaining.saturating_sub(events.len());
+        }
+
+        // Last page
+        if fetched < limit {
+            break;
+        }
+
+        if whole_timestamp {
+            // All the events at `oldest` have been copied: move to the older ones
+            if oldest == Timestamp::zero() {
+                break;
+            }
+
+            until = Some(oldest - 1);
+            copied_at_until.clear();
+        } else {
+            // The events at `oldest` may be split across pages: keep track of the copied ones
+            if until != Some(oldest) {
+                copied_at_until.clear();
+            }
+
+            copied_at_until.extend(
+                events
+                    .iter()
+                    .filter(|e| e.created_at == oldest)
+                    .map(|e| e.id),
+            );
+            until = Some(oldest);
+        }
+    }
+
+    Ok(copied)
+}
+
+async fn save_page(to: &dyn NostrEventsDatabase, events: &[Event]) -> Result<usize, DatabaseError> {
+    let statuses: Vec<SaveEventStatus> = to.save_events(events).await?;
+    Ok(statuses.iter().filter(|s| s.is_success()).count())
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Kind};
+
+    use super::*;
+    use crate::{MemoryDatabase, MemoryDatabaseOptions};
+
+    fn memory_database() -> MemoryDatabase {
+        MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_copy_database() {
+        let keys = Keys::generate();
+
+        let from = memory_database();
+        for i in 0..10 {
+            let event = EventBuilder::text_note(format!("Text note {i}"))
+                .sign_with_keys(&keys)
+                .unwrap();
+            from.save_event(&event).await.unwrap();
+        }
+
+        let metadata = EventBuilder::new(Kind::Metadata, "{}")
+            .sign_with_keys(&keys)
+            .unwrap();
+        from.save_event(&metadata).await.unwrap();
+
+        // Copy only the text notes
+        let to = memory_database();
+        let filter = Filter::new().kind(Kind::TextNote);
+        let copied = copy_database(&from, &to, filter.clone()).await.unwrap();
+        assert_eq!(copied, 10);
+        assert_eq!(
+            to.query(filter.clone()).await.unwrap(),
+            from.query(filter.clone()).await.unwrap()
+        );
+        assert_eq!(to.count(Filter::new()).await.unwrap(), 10);
+
+        // Already copied events aren't counted
+        let copied = copy_database(&from, &to, Filter::new()).await.unwrap();
+        assert_eq!(copied, 1);
+        assert_eq!(
+            to.query(Filter::new()).await.unwrap(),
+            from.query(Filter::new()).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_database_paged() {
+        let keys = Keys::generate();
+
+        let from = memory_database();
+        // 7 events at the same timestamp, more than the page size
+        for i in 0..7 {
+            let event = EventBuilder::text_note(format!("Same timestamp {i}"))
+                .custom_created_at(Timestamp::from(1000))
+                .sign_with_keys(&keys)
+                .unwrap();
+            from.save_event(&event).await.unwrap();
+        }
+        // Events at different timestamps, some of them split across the pages
+        for i in 0..10 {
+            let event = EventBuilder::text_note(format!("Text note {i}"))
+                .custom_created_at(Timestamp::from(100 + i / 2))
+                .sign_with_keys(&keys)
+                .unwrap();
+            from.save_event(&event).await.unwrap();
+        }
+
+        let to = memory_database();
+        let copied = copy_database_paged(&from, &to, Filter::new(), 3)
+            .await
+            .unwrap();
+        assert_eq!(copied, 17);
+        assert_eq!(
+            to.query(Filter::new()).await.unwrap(),
+            from.query(Filter::new()).await.unwrap()
+        );
+
+        // The filter limit is still respected
+        let to = memory_database();
+        let copied = copy_database_paged(&from, &to, Filter::new().limit(9), 3)
+            .await
+            .unwrap();
+        assert_eq!(copied, 9);
+        assert_eq!(
+            to.query(Filter::new()).await.unwrap(),
+            from.query(Filter::new().limit(9)).await.unwrap()
+        );
+    }
+}
@@ -8,12 +8,16 @@
 
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::iter;
+use std::mem;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
+use nostr::event::KindClass;
 use nostr::nips::nip01::{Coordinate, CoordinateBorrow};
+use nostr::types::{Clock, SystemClock};
 use nostr::{Alphabet, Event, EventId, Filter, Kind, PublicKey, SingleLetterTag, Timestamp};
-use tokio::sync::{OwnedRwLockReadGuard, RwLock};
+use tokio::sync::{OwnedRwLockReadGuard, RwLock, RwLockReadGuard};
 
 use crate::collections::tree::{BTreeCappedSet, Capacity, InsertResult, OverCapacityPolicy};
 use crate::{Events, RejectedReason, SaveEventStatus};
@@ -140,6 +144,17 @@ pub struct DatabaseEventResult {
     pub to_discard: HashSet<EventId>,
 }
 
+/// Deduplication statistics
+///
+/// Counters of the events processed by [`DatabaseHelper`] since its creation or the last clear/reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Number of indexed events
+    pub inserted: u64,
+    /// Number of events rejected because already indexed
+    pub duplicates: u64,
+}
+
 enum InternalQueryResult<'a> {
     All,
     Set(BTreeSet<&'a DatabaseEvent>),
@@ -155,8 +170,11 @@ struct InternalDatabaseHelper {
     author_index: HashMap<PublicKey, BTreeSet<DatabaseEvent>>,
     kind_author_index: HashMap<(Kind, PublicKey), BTreeSet<DatabaseEvent>>,
     param_replaceable_index: HashMap<(Kind, PublicKey, String), DatabaseEvent>,
+    /// Events by single-letter tag and value
+    tag_index: HashMap<SingleLetterTag, HashMap<String, BTreeSet<DatabaseEvent>>>,
     deleted_ids: HashSet<EventId>,
     deleted_coordinates: HashMap<Coordinate, Timestamp>,
+    dedup_stats: DedupStats,
 }
 
 impl InternalDatabaseHelper {
@@ -172,30 +190,32 @@ impl InternalDatabaseHelper {
     // Bulk load
     //
     // NOT CHANGE `events` ARG! Processing events in ASC it's much more performant
-    pub fn bulk_load(&mut self, events: BTreeSet<Event>) -> HashSet<EventId> {
-        let now: Timestamp = Timestamp::now();
+    pub fn bulk_load(&mut self, events: BTreeSet<Event>, now: Timestamp) -> HashSet<EventId> {
         events
             .into_iter()
             .rev() // Lookup ID: EVENT_ORD_IMPL
-            .filter(|e| !e.kind.is_ephemeral())
             .map(|event| self.internal_index_event(&event, &now))
             .flat_map(|res| res.to_discard)
             .collect()
     }
 
     /// Bulk import
-    pub fn bulk_import(&mut self, events: BTreeSet<Event>) -> impl Iterator<Item = Event> + '_ {
-        let now: Timestamp = Timestamp::now();
+    pub fn bulk_import(
+        &mut self,
+        events: BTreeSet<Event>,
+        now: Timestamp,
+    ) -> impl Iterator<Item = Event> + '_ {
         events
             .into_iter()
             .rev() // Lookup ID: EVENT_ORD_IMPL
-            .filter(|e| !e.is_expired() && !e.kind.is_ephemeral())
+            .filter(move |e| !e.is_expired_at(&now))
             .filter(move |event| self.internal_index_event(event, &now).status.is_success())
     }
 
     fn internal_index_event(&mut self, event: &Event, now: &Timestamp) -> DatabaseEventResult {
         // Check if was already added
         if self.ids.contains_key(&event.id) {
+            self.dedup_stats.duplicates += 1;
             return DatabaseEventResult {
                 status: SaveEventStatus::Rejected(RejectedReason::Duplicate),
                 to_discard: HashSet::new(),
@@ -263,13 +283,24 @@ impl InternalDatabaseHelper {
                 None => status = SaveEventStatus::Rejected(RejectedReason::Other),
             }
         } else if kind == Kind::EventDeletion {
+            // A deletion must only remove events of the same author:
+            // the references to the events of other authors are ignored.
+            let mut references: usize = 0;
+            let mut foreign: usize = 0;
+
             // Check `e` tags
             for id in event.tags.event_ids() {
+                references += 1;
+
                 if let Some(ev) = self.ids.get(id) {
                     if ev.pubkey != author {
-                        to_discard.insert(event.id);
-                        status = SaveEventStatus::Rejected(RejectedReason::InvalidDelete);
-                        break;
+                        foreign += 1;
+                        tracing::warn!(
+                            id = %event.id,
+                            target = %id,
+                            "Deletion event references an event of another author: ignoring it."
+                        );
+                        continue;
                     }
 
                     if ev.created_at <= created_at {
@@ -280,10 +311,16 @@ impl InternalDatabaseHelper {
 
             // Check `a` tags
             for coordinate in event.tags.coordinates() {
+                references += 1;
+
                 if coordinate.public_key != author {
-                    to_discard.insert(event.id);
-                    status = SaveEventStatus::Rejected(RejectedReason::InvalidDelete);
-                    break;
+                    foreign += 1;
+                    tracing::warn!(
+                        id = %event.id,
+                        target = %coordinate,
+                        "Deletion event references a coordinate of another author: ignoring it."
+                    );
+                    continue;
                 }
 
                 // Save deleted coordinate at certain timestamp
@@ -316,6 +353,12 @@ impl InternalDatabaseHelper {
                     to_discard.extend(self.internal_query_by_kind_and_author(params).map(|e| e.id));
                 }
             }
+
+            // Nothing to delete: all the references are owned by other authors
+            if references > 0 && foreign == references {
+                to_discard.insert(event.id);
+                status = SaveEventStatus::Rejected(RejectedReason::InvalidDelete);
+            }
         }
 
         // Remove events
@@ -328,6 +371,7 @@ impl InternalDatabaseHelper {
             let InsertResult { inserted, pop } = self.events.insert(e.clone());
 
             if inserted {
+                self.dedup_stats.inserted += 1;
                 self.ids.insert(e.id, e.clone());
                 self.author_index
                     .entry(author)
@@ -341,6 +385,8 @@ impl InternalDatabaseHelper {
                     }
                 }
 
+                self.index_tags(&e);
+
                 if kind.is_replaceable() {
                     let mut set = BTreeSet::new();
                     set.insert(e);
@@ -386,6 +432,8 @@ impl InternalDatabaseHelper {
                 if let Some(set) = self.kind_author_index.get_mut(&(ev.kind, ev.pubkey)) {
                     set.remove(&ev);
                 }
+
+                self.remove_tags_index(&ev);
             }
             self.deleted_ids.insert(*id);
         }
@@ -408,21 +456,70 @@ impl InternalDatabaseHelper {
         if let Some(set) = self.kind_author_index.get_mut(&(ev.kind, ev.pubkey)) {
             set.remove(&ev);
         }
+
+        self.remove_tags_index(&ev);
+    }
+
+    /// Index the single-letter tags of the event
+    fn index_tags(&mut self, ev: &DatabaseEvent) {
+        for tag in ev.tags.iter() {
+            if let (Some(single_letter), Some(value)) = (tag.single_letter_tag(), tag.content()) {
+                self.tag_index
+                    .entry(single_letter)
+                    .or_default()
+                    .entry(value.to_string())
+                    .or_default()
+                    .insert(ev.clone());
+            }
+        }
+    }
+
+    /// Remove the event from the tags index
+    fn remove_tags_index(&mut self, ev: &DatabaseEvent) {
+        for tag in ev.tags.iter() {
+            if let (Some(single_letter), Some(value)) = (tag.single_letter_tag(), tag.content()) {
+                if let Some(values) = self.tag_index.get_mut(&single_letter) {
+                    if let Some(set) = values.get_mut(value) {
+                        set.remove(ev);
+
+                        if set.is_empty() {
+                            values.remove(value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check if there are events created before the [`Timestamp`]
+    fn has_older_than(&self, threshold: Timestamp) -> bool {
+        // Lookup ID: EVENT_ORD_IMPL
+        match self.events.last() {
+            Some(ev) => ev.created_at < threshold,
+            None => false,
+        }
+    }
+
+    /// Evict the events created before the [`Timestamp`]
+    fn evict_older_than(&mut self, threshold: Timestamp) {
+        // Lookup ID: EVENT_ORD_IMPL
+        while let Some(ev) = self.events.last() {
+            if ev.created_at >= threshold {
+                break;
+            }
+
+            let ev: DatabaseEvent = ev.clone();
+            self.events.remove(&ev);
+            self.discard_event(ev);
+        }
     }
 
     /// Import [Event]
     ///
     /// **This method assume that [`Event`] was already verified**
-    pub fn index_event(&mut self, event: &Event) -> DatabaseEventResult {
-        // Check if it's ephemeral
-        if event.kind.is_ephemeral() {
-            return DatabaseEventResult {
-                status: SaveEventStatus::Rejected(RejectedReason::Ephemeral),
-                to_discard: HashSet::new(),
-            };
-        }
-        let now = Timestamp::now();
-        self.internal_index_event(event, &now)
+    #[inline]
+    pub fn index_event(&mut self, event: &Event, now: &Timestamp) -> DatabaseEventResult {
+        self.internal_index_event(event, now)
     }
 
     /// Query by public key
@@ -534,12 +631,42 @@ impl InternalDatabaseHelper {
         Some(ev)
     }
 
-    /// Generic query
-    #[inline]
-    fn internal_generic_query(&self, filter: Filter) -> impl Iterator<Item = &DatabaseEvent> {
-        self.events
+    /// Get the candidate events for the generic tags of the filter, using the tags index
+    ///
+    /// For every tag of the filter, collect the events matching at least one of the values,
+    /// and return the smallest set. Return `None` if the filter hasn't generic tags.
+    fn internal_query_tags_candidates(&self, filter: &Filter) -> Option<BTreeSet<&DatabaseEvent>> {
+        filter
+            .generic_tags
             .iter()
-            .filter(move |event| !self.deleted_ids.contains(&event.id) && filter.match_event(event))
+            .map(
+                |(single_letter, values)| match self.tag_index.get(single_letter) {
+                    Some(index) => values
+                        .iter()
+                        .filter_map(|value| index.get(value))
+                        .flatten()
+                        .collect(),
+                    None => BTreeSet::new(),
+                },
+            )
+            .min_by_key(|set| set.len())
+    }
+
+    /// Generic query
+    ///
+    /// If the filter has generic tags, only the candidates returned by the tags index are scanned.
+    fn internal_generic_query(
+        &self,
+        filter: Filter,
+    ) -> Box<dyn Iterator<Item = &DatabaseEvent> + '_> {
+        match self.internal_query_tags_candidates(&filter) {
+            Some(candidates) => Box::new(candidates.into_iter().filter(move |event| {
+                !self.deleted_ids.contains(&event.id) && filter.match_event(event)
+            })),
+            None => Box::new(self.events.iter().filter(move |event| {
+                !self.deleted_ids.contains(&event.id) && filter.match_event(event)
+            })),
+        }
     }
 
     fn internal_query(&self, filter: Filter) -> InternalQueryResult {
@@ -565,7 +692,7 @@ impl InternalDatabaseHelper {
                     None => Box::new(iter::empty()),
                 }
             }
-            QueryPattern::Generic(filter) => Box::new(self.internal_generic_query(*filter)),
+            QueryPattern::Generic(filter) => self.internal_generic_query(*filter),
         };
 
         if let Some(limit) = limit {
@@ -648,6 +775,66 @@ impl InternalDatabaseHelper {
         }
     }
 
+    /// Delete the events by ID, returning the IDs of the deleted events
+    pub fn delete_by_ids(&mut self, ids: &[EventId]) -> HashSet<EventId> {
+        let ids: HashSet<EventId> = ids
+            .iter()
+            .filter(|id| self.ids.contains_key(*id))
+            .copied()
+            .collect();
+        self.discard_events(&ids);
+        ids
+    }
+
+    /// Estimate the memory used by the events and the indexes, in bytes
+    pub fn estimated_bytes(&self) -> usize {
+        const PTR: usize = mem::size_of::<DatabaseEvent>();
+
+        // Events (and the `Arc` counters)
+        let mut size: usize = self
+            .events
+            .iter()
+            .map(|e| estimated_event_size(e) + 2 * mem::size_of::<usize>())
+            .sum();
+
+        // Indexes
+        size += self.events.len() * PTR;
+        size += self.ids.len() * (mem::size_of::<EventId>() + PTR);
+        size += self
+            .author_index
+            .values()
+            .map(|set| mem::size_of::<PublicKey>() + set.len() * PTR)
+            .sum::<usize>();
+        size += self
+            .kind_author_index
+            .values()
+            .map(|set| mem::size_of::<(Kind, PublicKey)>() + set.len() * PTR)
+            .sum::<usize>();
+        size += self
+            .param_replaceable_index
+            .keys()
+            .map(|(.., identifier)| {
+                mem::size_of::<(Kind, PublicKey, String)>() + identifier.len() + PTR
+            })
+            .sum::<usize>();
+        size += self
+            .tag_index
+            .values()
+            .flat_map(|values| values.iter())
+            .map(|(value, set)| mem::size_of::<String>() + value.len() + set.len() * PTR)
+            .sum::<usize>();
+
+        // Deletions
+        size += self.deleted_ids.len() * mem::size_of::<EventId>();
+        size += self
+            .deleted_coordinates
+            .keys()
+            .map(|c| mem::size_of::<(Coordinate, Timestamp)>() + c.identifier.len())
+            .sum::<usize>();
+
+        size
+    }
+
     pub fn clear(&mut self) {
         // Get current capacity
         let capacity: Capacity = self.events.capacity();
@@ -666,9 +853,27 @@ pub struct QueryTransaction {
 }
 
 /// Database Indexes
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct DatabaseHelper {
     inner: Arc<RwLock<InternalDatabaseHelper>>,
+    clock: Arc<dyn Clock>,
+    store_ephemeral: bool,
+    kinds_whitelist: Option<HashSet<Kind>>,
+    max_age: Option<Duration>,
+    max_future_drift: Option<Duration>,
+}
+
+impl Default for DatabaseHelper {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(InternalDatabaseHelper::default())),
+            clock: Arc::new(SystemClock),
+            store_ephemeral: false,
+            kinds_whitelist: None,
+            max_age: None,
+            max_future_drift: None,
+        }
+    }
 }
 
 impl DatabaseHelper {
@@ -683,54 +888,260 @@ impl DatabaseHelper {
     pub fn bounded(max: usize) -> Self {
         Self {
             inner: Arc::new(RwLock::new(InternalDatabaseHelper::bounded(max))),
+            clock: Arc::new(SystemClock),
+            store_ephemeral: false,
+            kinds_whitelist: None,
+            max_age: None,
+            max_future_drift: None,
+        }
+    }
+
+    /// Set a custom [`Clock`]
+    ///
+    /// By default [`SystemClock`] is used.
+    #[inline]
+    pub fn clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Store ephemeral events (default: false)
+    ///
+    /// Ephemeral events aren't expected to be stored (NIP-01): enable it only for debugging purposes.
+    #[inline]
+    pub fn store_ephemeral(mut self, enable: bool) -> Self {
+        self.store_ephemeral = enable;
+        self
+    }
+
+    /// Store only the events of the specified kinds
+    ///
+    /// Events of other kinds are rejected with [`RejectedReason::KindNotAllowed`].
+    #[inline]
+    pub fn kinds_whitelist<I>(mut self, kinds: I) -> Self
+    where
+        I: IntoIterator<Item = Kind>,
+    {
+        self.kinds_whitelist = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// Max age of the stored events
+    ///
+    /// Events older than the max age are rejected with [`RejectedReason::TooOld`] and the stored ones are lazily evicted
+    /// when the helper is accessed. The age is computed from the `created_at` of the events.
+    #[inline]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Max drift in the future of the `created_at` of the events
+    ///
+    /// Events with a `created_at` greater than `now + drift` are rejected with [`RejectedReason::FutureDated`].
+    #[inline]
+    pub fn max_future_drift(mut self, drift: Duration) -> Self {
+        self.max_future_drift = Some(drift);
+        self
+    }
+
+    /// Check if the event `created_at` is beyond the max future drift, if set
+    #[inline]
+    fn is_future_dated(&self, event: &Event) -> bool {
+        match self.max_future_drift {
+            Some(drift) => event.created_at > self.clock.now() + drift,
+            None => false,
+        }
+    }
+
+    /// Get the min `created_at` allowed by the max age, if set
+    #[inline]
+    fn max_age_threshold(&self) -> Option<Timestamp> {
+        self.max_age.map(|max_age| self.clock.now() - max_age)
+    }
+
+    /// Acquire a read lock, evicting the over-age events first (if max age is set)
+    ///
+    /// The write lock is acquired only if there are events to evict.
+    async fn read(&self) -> RwLockReadGuard<'_, InternalDatabaseHelper> {
+        let inner = self.inner.read().await;
+
+        match self.max_age_threshold() {
+            Some(threshold) if inner.has_older_than(threshold) => {
+                drop(inner);
+
+                let mut inner = self.inner.write().await;
+                inner.evict_older_than(threshold);
+                inner.downgrade()
+            }
+            _ => inner,
+        }
+    }
+
+    /// Check if the [`Kind`] can be stored
+    fn check_kind(&self, kind: &Kind) -> Result<(), RejectedReason> {
+        if let KindClass::Ephemeral = kind.classification() {
+            if !self.store_ephemeral {
+                return Err(RejectedReason::Ephemeral);
+            }
+        }
+
+        match &self.kinds_whitelist {
+            Some(whitelist) if !whitelist.contains(kind) => Err(RejectedReason::KindNotAllowed),
+            _ => Ok(()),
         }
     }
 
     /// Query transaction
     #[inline]
     pub async fn qtxn(&self) -> QueryTransaction {
-        QueryTransaction {
-            guard: self.inner.clone().read_owned().await,
-        }
+        let guard: OwnedRwLockReadGuard<InternalDatabaseHelper> =
+            self.inner.clone().read_owned().await;
+
+        // Evict the over-age events, acquiring the write lock only if needed
+        let guard: OwnedRwLockReadGuard<InternalDatabaseHelper> = match self.max_age_threshold() {
+            Some(threshold) if guard.has_older_than(threshold) => {
+                drop(guard);
+
+                let mut inner = self.inner.clone().write_owned().await;
+                inner.evict_older_than(threshold);
+                inner.downgrade()
+            }
+            _ => guard,
+        };
+
+        QueryTransaction { guard }
     }
 
     /// Bulk index
-    pub async fn bulk_load(&self, events: BTreeSet<Event>) -> HashSet<EventId> {
+    pub async fn bulk_load(&self, mut events: BTreeSet<Event>) -> HashSet<EventId> {
+        let threshold: Option<Timestamp> = self.max_age_threshold();
+        events.retain(|e| {
+            self.check_kind(&e.kind).is_ok()
+                && !is_over_age(e, threshold)
+                && !self.is_future_dated(e)
+        });
+        let now: Timestamp = self.clock.now();
         let mut inner = self.inner.write().await;
-        inner.bulk_load(events)
+        if let Some(threshold) = threshold {
+            inner.evict_older_than(threshold);
+        }
+        inner.bulk_load(events, now)
     }
 
     /// Bulk import
     ///
     /// Take a set of [Event], index them and return **only** the ones that must be stored into the database
-    pub async fn bulk_import(&self, events: BTreeSet<Event>) -> BTreeSet<Event> {
+    pub async fn bulk_import(&self, mut events: BTreeSet<Event>) -> BTreeSet<Event> {
+        let threshold: Option<Timestamp> = self.max_age_threshold();
+        events.retain(|e| {
+            self.check_kind(&e.kind).is_ok()
+                && !is_over_age(e, threshold)
+                && !self.is_future_dated(e)
+        });
+        let now: Timestamp = self.clock.now();
         let mut inner = self.inner.write().await;
-        inner.bulk_import(events).collect()
+        if let Some(threshold) = threshold {
+            inner.evict_older_than(threshold);
+        }
+        inner.bulk_import(events, now).collect()
+    }
+
+    /// Check if the [`Event`] can be indexed, without locking the indexes
+    fn pre_index_check(
+        &self,
+        event: &Event,
+        threshold: Option<Timestamp>,
+    ) -> Result<(), RejectedReason> {
+        self.check_kind(&event.kind)?;
+
+        if self.is_future_dated(event) {
+            return Err(RejectedReason::FutureDated);
+        }
+
+        if is_over_age(event, threshold) {
+            return Err(RejectedReason::TooOld);
+        }
+
+        Ok(())
     }
 
     /// Index [`Event`]
     ///
     /// **This method assumes that [`Event`] was already verified**
     pub async fn index_event(&self, event: &Event) -> DatabaseEventResult {
+        let threshold: Option<Timestamp> = self.max_age_threshold();
+        if let Err(reason) = self.pre_index_check(event, threshold) {
+            return DatabaseEventResult {
+                status: SaveEventStatus::Rejected(reason),
+                to_discard: HashSet::new(),
+            };
+        }
+
+        let now: Timestamp = self.clock.now();
         let mut inner = self.inner.write().await;
-        inner.index_event(event)
+        if let Some(threshold) = threshold {
+            inner.evict_older_than(threshold);
+        }
+        inner.index_event(event, &now)
+    }
+
+    /// Index a batch of [`Event`]s, acquiring the write lock only once
+    ///
+    /// The results are returned in the same order of `events`.
+    ///
+    /// **This method assumes that the [`Event`]s were already verified**
+    pub async fn index_events<'a, I>(&self, events: I) -> Vec<DatabaseEventResult>
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        let threshold: Option<Timestamp> = self.max_age_threshold();
+        let now: Timestamp = self.clock.now();
+        let mut inner = self.inner.write().await;
+        if let Some(threshold) = threshold {
+            inner.evict_older_than(threshold);
+        }
+
+        events
+            .into_iter()
+            .map(|event| match self.pre_index_check(event, threshold) {
+                Ok(()) => inner.index_event(event, &now),
+                Err(reason) => DatabaseEventResult {
+                    status: SaveEventStatus::Rejected(reason),
+                    to_discard: HashSet::new(),
+                },
+            })
+            .collect()
     }
 
     /// Get [Event] by ID
     pub async fn event_by_id(&self, id: &EventId) -> Option<Event> {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         inner.event_by_id(id).cloned()
     }
 
+    /// Get [Event]s by IDs
+    ///
+    /// The found events are returned in the same order of `ids`.
+    pub async fn events_by_ids(&self, ids: &[EventId]) -> Vec<Event> {
+        let inner = self.read().await;
+        ids.iter()
+            .filter_map(|id| inner.event_by_id(id).cloned())
+            .collect()
+    }
+
     /// Check if event exists
     pub async fn has_event(&self, id: &EventId) -> bool {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         inner.has_event(id)
     }
 
     /// Query
     pub async fn query(&self, filter: Filter) -> Events {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         let mut events = Events::new(&filter);
         events.extend(inner.query(filter).cloned());
         events
@@ -747,19 +1158,19 @@ impl DatabaseHelper {
 
     /// Count events
     pub async fn count(&self, filter: Filter) -> usize {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         inner.count(filter)
     }
 
     /// Get negentropy items
     pub async fn negentropy_items(&self, filter: Filter) -> Vec<(EventId, Timestamp)> {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         inner.negentropy_items(filter)
     }
 
     /// Check if an event with [`EventId`] has been deleted
     pub async fn has_event_id_been_deleted(&self, event_id: &EventId) -> bool {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         inner.has_event_id_been_deleted(event_id)
     }
 
@@ -769,7 +1180,7 @@ impl DatabaseHelper {
         coordinate: &'a CoordinateBorrow<'a>,
         timestamp: &Timestamp,
     ) -> bool {
-        let inner = self.inner.read().await;
+        let inner = self.read().await;
         inner.has_coordinate_been_deleted(&coordinate.into_owned(), timestamp)
     }
 
@@ -781,6 +1192,62 @@ impl DatabaseHelper {
         inner.delete(filter)
     }
 
+    /// Delete the events by ID, returning the IDs of the deleted events
+    pub async fn delete_by_ids(&self, ids: &[EventId]) -> HashSet<EventId> {
+        let mut inner = self.inner.write().await;
+        inner.delete_by_ids(ids)
+    }
+
+    /// Estimate the memory used by the events and the indexes, in bytes
+    pub async fn estimated_bytes(&self) -> usize {
+        let inner = self.read().await;
+        inner.estimated_bytes()
+    }
+
+    /// Get the deduplication statistics
+    pub async fn dedup_stats(&self) -> DedupStats {
+        let inner = self.read().await;
+        inner.dedup_stats
+    }
+
+    /// Reset the deduplication statistics
+    pub async fn reset_dedup_stats(&self) {
+        let mut inner = self.inner.write().await;
+        inner.dedup_stats = DedupStats::default();
+    }
+
+    /// Get the deleted event IDs and coordinates (tombstones), with the timestamp of the deletion request
+    pub async fn tombstones(&self) -> (HashSet<EventId>, HashMap<Coordinate, Timestamp>) {
+        let inner = self.read().await;
+        (inner.deleted_ids.clone(), inner.deleted_coordinates.clone())
+    }
+
+    /// Restore the deleted event IDs and coordinates (tombstones)
+    ///
+    /// The events matching the tombstones are rejected when saved later.
+    /// See [`DatabaseHelper::tombstones`].
+    pub async fn restore_tombstones(
+        &self,
+        ids: HashSet<EventId>,
+        coordinates: HashMap<Coordinate, Timestamp>,
+    ) {
+        let mut inner = self.inner.write().await;
+        inner.deleted_ids.extend(ids);
+
+        for (coordinate, deleted_at) in coordinates.into_iter() {
+            inner
+                .deleted_coordinates
+                .entry(coordinate)
+                .and_modify(|t| {
+                    // Update only if newer
+                    if deleted_at > *t {
+                        *t = deleted_at
+                    }
+                })
+                .or_insert(deleted_at);
+        }
+    }
+
     /// Clear helper
     pub async fn clear(&self) {
         let mut inner = self.inner.write().await;
@@ -788,9 +1255,37 @@ impl DatabaseHelper {
     }
 }
 
+/// Estimate the memory used by an event, in bytes
+fn estimated_event_size(event: &Event) -> usize {
+    let tags: usize = event
+        .tags
+        .iter()
+        .map(|tag| {
+            mem::size_of_val(tag)
+                + tag
+                    .as_slice()
+                    .iter()
+                    .map(|v| mem::size_of::<String>() + v.len())
+                    .sum::<usize>()
+        })
+        .sum();
+    mem::size_of::<Event>() + event.content.len() + tags
+}
+
+#[inline]
+fn is_over_age(event: &Event, threshold: Option<Timestamp>) -> bool {
+    match threshold {
+        Some(threshold) => event.created_at < threshold,
+        None => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use nostr::{FromBech32, JsonUtil, Keys, SecretKey};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use nostr::nips::nip09::EventDeletionRequest;
+    use nostr::{EventBuilder, FromBech32, JsonUtil, Keys, SecretKey, Tag};
 
     use super::*;
 
@@ -1017,4 +1512,240 @@ mod tests {
             vec![ev]
         );
     }
+
+    #[derive(Debug, Clone)]
+    struct MockClock {
+        now: Arc<AtomicU64>,
+    }
+
+    impl MockClock {
+        fn advance(&self, secs: u64) {
+            self.now.fetch_add(secs, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Timestamp {
+            Timestamp::from_secs(self.now.load(Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    fn test_tags_index() {
+        let keys = Keys::generate();
+        let now = Timestamp::now();
+
+        let mut helper = InternalDatabaseHelper::default();
+        for i in 0..1000u64 {
+            let event = EventBuilder::text_note("Text note")
+                .tag(Tag::hashtag(format!("tag-{}", i % 100)))
+                .custom_created_at(Timestamp::from(1704644581 + i))
+                .sign_with_keys(&keys)
+                .unwrap();
+            helper.index_event(&event, &now);
+        }
+
+        let filter = Filter::new().hashtag("tag-7");
+
+        // Only the events with the tag are scanned
+        let candidates = helper.internal_query_tags_candidates(&filter).unwrap();
+        assert_eq!(candidates.len(), 10);
+        assert_eq!(helper.count(filter.clone()), 10);
+
+        // Matching multiple tags: the smallest set is used
+        let filter = Filter::new()
+            .hashtags(["tag-1", "tag-2", "tag-3"])
+            .event(EventId::all_zeros());
+        let candidates = helper.internal_query_tags_candidates(&filter).unwrap();
+        assert!(candidates.is_empty());
+        assert_eq!(helper.count(filter), 0);
+
+        // Index updated on delete
+        let filter = Filter::new().hashtag("tag-7");
+        helper.delete(filter.clone().limit(5));
+        let candidates = helper.internal_query_tags_candidates(&filter).unwrap();
+        assert_eq!(candidates.len(), 5);
+        assert_eq!(helper.count(filter), 5);
+    }
+
+    #[tokio::test]
+    async fn test_expiration_with_mock_clock() {
+        let keys = Keys::generate();
+
+        let clock = MockClock {
+            now: Arc::new(AtomicU64::new(1_700_000_000)),
+        };
+        let indexes = DatabaseHelper::unbounded().clock(clock.clone());
+
+        let expiration = Timestamp::from_secs(1_700_000_010);
+        let event_a = EventBuilder::text_note("A")
+            .tag(Tag::expiration(expiration))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let event_b = EventBuilder::text_note("B")
+            .tag(Tag::expiration(expiration))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // Not expired yet
+        let res = indexes.index_event(&event_a).await;
+        assert_eq!(res.status, SaveEventStatus::Success);
+
+        // Move the clock after the expiration
+        clock.advance(20);
+
+        let res = indexes.index_event(&event_b).await;
+        assert_eq!(
+            res.status,
+            SaveEventStatus::Rejected(RejectedReason::Expired)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_age_with_mock_clock() {
+        let keys = Keys::generate();
+
+        let clock = MockClock {
+            now: Arc::new(AtomicU64::new(1_700_000_000)),
+        };
+        let indexes = DatabaseHelper::unbounded()
+            .clock(clock.clone())
+            .max_age(Duration::from_secs(3600));
+
+        let old = EventBuilder::text_note("Old")
+            .custom_created_at(Timestamp::from_secs(1_700_000_000 - 1800))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let fresh = EventBuilder::text_note("Fresh")
+            .custom_created_at(Timestamp::from_secs(1_700_000_000))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let over_age = EventBuilder::text_note("Over age")
+            .custom_created_at(Timestamp::from_secs(1_700_000_000 - 7200))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        assert_eq!(
+            indexes.index_event(&old).await.status,
+            SaveEventStatus::Success
+        );
+        assert_eq!(
+            indexes.index_event(&fresh).await.status,
+            SaveEventStatus::Success
+        );
+        assert_eq!(
+            indexes.index_event(&over_age).await.status,
+            SaveEventStatus::Rejected(RejectedReason::TooOld)
+        );
+        assert_eq!(indexes.count(Filter::new()).await, 2);
+
+        // Move the clock: the old event is now over age
+        clock.advance(2000);
+
+        assert!(!indexes.has_event(&old.id).await);
+        assert!(indexes.has_event(&fresh.id).await);
+        assert_eq!(indexes.count(Filter::new()).await, 1);
+        assert_eq!(
+            indexes.query(Filter::new()).await.to_vec(),
+            vec![fresh.clone()]
+        );
+
+        // Not marked as deleted
+        assert!(!indexes.has_event_id_been_deleted(&old.id).await);
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_event() {
+        let keys = Keys::generate();
+        let event = EventBuilder::new(Kind::Custom(20_000), "Ephemeral")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // Rejected by default
+        let indexes = DatabaseHelper::unbounded();
+        let res = indexes.index_event(&event).await;
+        assert_eq!(
+            res.status,
+            SaveEventStatus::Rejected(RejectedReason::Ephemeral)
+        );
+        assert!(indexes.event_by_id(&event.id).await.is_none());
+
+        // Opt-out
+        let indexes = DatabaseHelper::unbounded().store_ephemeral(true);
+        let res = indexes.index_event(&event).await;
+        assert_eq!(res.status, SaveEventStatus::Success);
+        assert_eq!(indexes.event_by_id(&event.id).await, Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_deletion_of_another_author_events() {
+        let keys_a = Keys::generate();
+        let keys_b = Keys::generate();
+
+        let note_a = EventBuilder::text_note("Note A")
+            .sign_with_keys(&keys_a)
+            .unwrap();
+        let note_b = EventBuilder::text_note("Note B")
+            .sign_with_keys(&keys_b)
+            .unwrap();
+
+        let indexes = DatabaseHelper::unbounded();
+        indexes.index_event(&note_a).await;
+        indexes.index_event(&note_b).await;
+
+        // B tries to delete both its own note and the note of A
+        let deletion =
+            EventBuilder::delete(EventDeletionRequest::new().id(note_b.id).id(note_a.id))
+                .sign_with_keys(&keys_b)
+                .unwrap();
+        let res = indexes.index_event(&deletion).await;
+        assert_eq!(res.status, SaveEventStatus::Success);
+        assert!(indexes.has_event(&deletion.id).await);
+
+        // Only the note of B has been deleted
+        assert!(indexes.has_event(&note_a.id).await);
+        assert!(!indexes.has_event_id_been_deleted(&note_a.id).await);
+        assert!(!indexes.has_event(&note_b.id).await);
+        assert!(indexes.has_event_id_been_deleted(&note_b.id).await);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_stats() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..5)
+            .map(|i| {
+                EventBuilder::text_note(format!("Note #{i}"))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        let indexes = DatabaseHelper::unbounded();
+
+        // 5 new events, then 3 duplicates
+        for event in events.iter().chain(events.iter().take(3)) {
+            indexes.index_event(event).await;
+        }
+
+        assert_eq!(
+            indexes.dedup_stats().await,
+            DedupStats {
+                inserted: 5,
+                duplicates: 3,
+            }
+        );
+
+        // Bulk load counts too
+        indexes.bulk_load(events.iter().cloned().collect()).await;
+        assert_eq!(
+            indexes.dedup_stats().await,
+            DedupStats {
+                inserted: 5,
+                duplicates: 8,
+            }
+        );
+
+        indexes.reset_dedup_stats().await;
+        assert_eq!(indexes.dedup_stats().await, DedupStats::default());
+    }
 }
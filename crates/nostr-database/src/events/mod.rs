@@ -7,6 +7,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use nostr::prelude::*;
+use tokio::sync::broadcast;
 
 pub mod helper;
 
@@ -37,10 +38,18 @@ pub enum RejectedReason {
     Deleted,
     /// The event is expired
     Expired,
+    /// The event is older than the max age allowed by the database
+    TooOld,
     /// The event was replaced
     Replaced,
     /// Attempt to delete a non-owned event
     InvalidDelete,
+    /// The event kind isn't allowed to be stored
+    KindNotAllowed,
+    /// The event was rejected by the admission policy
+    Policy,
+    /// The event `created_at` is too far in the future
+    FutureDated,
     /// Other reason
     Other,
 }
@@ -62,6 +71,17 @@ impl SaveEventStatus {
     }
 }
 
+/// Database change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseChange {
+    /// The event has been saved
+    Saved(Box<Event>),
+    /// The events have been deleted (or replaced)
+    Deleted(HashSet<EventId>),
+    /// All the events have been deleted
+    Wiped,
+}
+
 #[doc(hidden)]
 pub trait IntoNostrEventsDatabase {
     fn into_database(self) -> Arc<dyn NostrEventsDatabase>;
@@ -103,6 +123,25 @@ pub trait NostrEventsDatabase: fmt::Debug + Send + Sync {
         event: &'a Event,
     ) -> BoxedFuture<'a, Result<SaveEventStatus, DatabaseError>>;
 
+    /// Save a batch of [`Event`]s into store
+    ///
+    /// The statuses are returned in the same order of `events`.
+    /// By default, the events are saved one by one: backends should override it with a batched write.
+    ///
+    /// **This method assumes that the [`Event`]s were already verified**
+    fn save_events<'a>(
+        &'a self,
+        events: &'a [Event],
+    ) -> BoxedFuture<'a, Result<Vec<SaveEventStatus>, DatabaseError>> {
+        Box::pin(async move {
+            let mut statuses: Vec<SaveEventStatus> = Vec::with_capacity(events.len());
+            for event in events.iter() {
+                statuses.push(self.save_event(event).await?);
+            }
+            Ok(statuses)
+        })
+    }
+
     /// Check event status by ID
     ///
     /// Check if the event is saved, deleted or not existent.
@@ -125,6 +164,25 @@ pub trait NostrEventsDatabase: fmt::Debug + Send + Sync {
         event_id: &'a EventId,
     ) -> BoxedFuture<'a, Result<Option<Event>, DatabaseError>>;
 
+    /// Get multiple [`Event`]s by [`EventId`]
+    ///
+    /// The found events are returned in the same order of the `ids` arg. Missing events are skipped.
+    fn events_by_ids<'a>(
+        &'a self,
+        ids: &'a [EventId],
+    ) -> BoxedFuture<'a, Result<Vec<Event>, DatabaseError>> {
+        Box::pin(async move {
+            let filter: Filter = Filter::new().ids(ids.iter().copied());
+            let mut events: HashMap<EventId, Event> = self
+                .query(filter)
+                .await?
+                .into_iter()
+                .map(|e| (e.id, e))
+                .collect();
+            Ok(ids.iter().filter_map(|id| events.remove(id)).collect())
+        })
+    }
+
     /// Count the number of events found with [`Filter`].
     ///
     /// Use `Filter::new()` or `Filter::default()` to count all events.
@@ -133,6 +191,32 @@ pub trait NostrEventsDatabase: fmt::Debug + Send + Sync {
     /// Query stored events.
     fn query(&self, filter: Filter) -> BoxedFuture<Result<Events, DatabaseError>>;
 
+    /// Query stored events that match the [`Filter`] and have at least one tag of the given kind, regardless of its value
+    ///
+    /// The `limit` of the filter is applied to the events having the tag.
+    fn query_with_tag_present(
+        &self,
+        base: Filter,
+        tag: SingleLetterTag,
+    ) -> BoxedFuture<Result<Events, DatabaseError>> {
+        Box::pin(async move {
+            let mut events: Events = Events::new(&base);
+
+            // Query without limit, since the tag check happens later
+            let mut filter: Filter = base;
+            filter.limit = None;
+
+            let stored: Events = self.query(filter).await?;
+            events.extend(
+                stored
+                    .into_iter()
+                    .filter(|e| e.tags.find(TagKind::SingleLetter(tag)).is_some()),
+            );
+
+            Ok(events)
+        })
+    }
+
     /// Get `negentropy` items
     fn negentropy_items(
         &self,
@@ -146,6 +230,33 @@ pub trait NostrEventsDatabase: fmt::Debug + Send + Sync {
 
     /// Delete all events that match the [Filter]
     fn delete(&self, filter: Filter) -> BoxedFuture<Result<(), DatabaseError>>;
+
+    /// Delete the events by [`EventId`]
+    ///
+    /// Return the number of deleted events.
+    fn delete_by_ids<'a>(
+        &'a self,
+        ids: &'a [EventId],
+    ) -> BoxedFuture<'a, Result<usize, DatabaseError>> {
+        Box::pin(async move {
+            if ids.is_empty() {
+                return Ok(0);
+            }
+
+            let filter: Filter = Filter::new().ids(ids.iter().copied());
+            let count: usize = self.count(filter.clone()).await?;
+            self.delete(filter).await?;
+            Ok(count)
+        })
+    }
+
+    /// Subscribe to the database changes
+    ///
+    /// By default, if the backend doesn't support it, the returned receiver is already closed.
+    fn subscribe_changes(&self) -> broadcast::Receiver<DatabaseChange> {
+        let (_, rx) = broadcast::channel(1);
+        rx
+    }
 }
 
 /// Nostr Event Store Extension
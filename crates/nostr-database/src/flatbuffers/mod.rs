@@ -11,7 +11,6 @@ use flatbuffers::InvalidFlatbuffer;
 pub use flatbuffers::{FlatBufferBuilder, ForwardsUOffset, Vector};
 use nostr::prelude::*;
 use nostr::secp256k1;
-use nostr::secp256k1::schnorr::Signature;
 
 #[allow(unused_imports, dead_code, clippy::all, unsafe_code, missing_docs)]
 mod event_generated;
@@ -130,14 +129,14 @@ impl FlatBufferDecode for Event {
             .filter_map(|tag| tag.data().map(Tag::parse))
             .collect::<Result<Vec<Tag>, _>>()?;
 
-        Ok(Self::new(
-            EventId::from_byte_array(ev.id().ok_or(Error::NotFound)?.0),
-            PublicKey::from_byte_array(ev.pubkey().ok_or(Error::NotFound)?.0),
+        Ok(Self::from_raw_parts(
+            ev.id().ok_or(Error::NotFound)?.0,
+            ev.pubkey().ok_or(Error::NotFound)?.0,
             Timestamp::from(ev.created_at()),
             Kind::from(ev.kind() as u16),
             tags,
-            ev.content().ok_or(Error::NotFound)?.to_owned(),
-            Signature::from_slice(&ev.sig().ok_or(Error::NotFound)?.0)?,
+            ev.content().ok_or(Error::NotFound)?,
+            ev.sig().ok_or(Error::NotFound)?.0,
         ))
     }
 }
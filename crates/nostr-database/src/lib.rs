@@ -14,25 +14,29 @@ use std::sync::Arc;
 pub use nostr;
 
 mod collections;
+mod copy;
 mod error;
 mod events;
 #[cfg(feature = "flatbuf")]
 pub mod flatbuffers;
 pub mod memory;
+mod policy;
 pub mod prelude;
 pub mod profile;
 mod wipe;
 
-pub use self::collections::events::Events;
+pub use self::collections::events::{Events, EventsDiff};
+pub use self::copy::copy_database;
 pub use self::error::DatabaseError;
-pub use self::events::helper::{DatabaseEventResult, DatabaseHelper};
+pub use self::events::helper::{DatabaseEventResult, DatabaseHelper, DedupStats};
 pub use self::events::{
-    DatabaseEventStatus, IntoNostrEventsDatabase, NostrEventsDatabase, NostrEventsDatabaseExt,
-    RejectedReason, SaveEventStatus,
+    DatabaseChange, DatabaseEventStatus, IntoNostrEventsDatabase, NostrEventsDatabase,
+    NostrEventsDatabaseExt, RejectedReason, SaveEventStatus,
 };
 #[cfg(feature = "flatbuf")]
 pub use self::flatbuffers::{FlatBufferBuilder, FlatBufferDecode, FlatBufferEncode};
-pub use self::memory::{MemoryDatabase, MemoryDatabaseOptions};
+pub use self::memory::{EvictionPolicy, MemoryDatabase, MemoryDatabaseOptions};
+pub use self::policy::DatabaseAdmitPolicy;
 pub use self::profile::Profile;
 pub use self::wipe::NostrDatabaseWipe;
 
@@ -4,22 +4,49 @@
 
 //! Memory (RAM) Storage backend for Nostr apps
 
+#[cfg(feature = "snapshot")]
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+#[cfg(feature = "snapshot")]
+use std::io::{self, Read, Write};
+use std::mem;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 use lru::LruCache;
 use nostr::prelude::*;
-use tokio::sync::RwLock;
+#[cfg(feature = "snapshot")]
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, RwLock};
 
 use crate::{
-    Backend, DatabaseError, DatabaseEventResult, DatabaseEventStatus, DatabaseHelper, Events,
-    NostrDatabase, NostrDatabaseWipe, NostrEventsDatabase, SaveEventStatus,
+    Backend, DatabaseAdmitPolicy, DatabaseChange, DatabaseError, DatabaseEventResult,
+    DatabaseEventStatus, DatabaseHelper, DedupStats, Events, NostrDatabase, NostrDatabaseWipe,
+    NostrEventsDatabase, RejectedReason, SaveEventStatus,
 };
 
 const MAX_EVENTS: usize = 35_000;
+const CHANGES_CHANNEL_SIZE: usize = 1024;
+#[cfg(feature = "snapshot")]
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// Eviction policy of the seen event IDs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EvictionPolicy {
+    /// Evict the first seen ID
+    ///
+    /// Checking or saving again an already seen ID doesn't change its position.
+    #[default]
+    Fifo,
+    /// Evict the least recently used ID
+    ///
+    /// Checking or saving again an already seen ID marks it as the most recent one.
+    Lru,
+}
 
 /// Database options
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone)]
 pub struct MemoryDatabaseOptions {
     /// Store events (default: false)
     pub events: bool,
@@ -29,6 +56,42 @@ pub struct MemoryDatabaseOptions {
     ///
     /// If `Some(0)` is passed, the default value will be used.
     pub max_events: Option<usize>,
+    /// Store ephemeral events (default: false)
+    ///
+    /// Ephemeral events aren't expected to be stored (NIP-01): enable it only for debugging purposes.
+    pub store_ephemeral: bool,
+    /// Store only the events of these kinds (default: None)
+    ///
+    /// `None` means that all kinds are allowed.
+    /// This has effect only if events storing is enabled (see [`MemoryDatabaseOptions::events`]).
+    pub kinds_whitelist: Option<HashSet<Kind>>,
+    /// Max age of the stored events (default: None)
+    ///
+    /// Events older than the max age are rejected and the stored ones are lazily evicted
+    /// (no background task) when the database is accessed.
+    /// This complements [`MemoryDatabaseOptions::max_events`].
+    ///
+    /// `None` means no limits.
+    /// This has effect only if events storing is enabled (see [`MemoryDatabaseOptions::events`]).
+    pub max_age: Option<Duration>,
+    /// Max drift in the future of the `created_at` of the events (default: None)
+    ///
+    /// Events with a `created_at` greater than `now + drift` are rejected with [`RejectedReason::FutureDated`].
+    ///
+    /// `None` means no limits.
+    /// This has effect only if events storing is enabled (see [`MemoryDatabaseOptions::events`])
+    /// and isn't persisted in the snapshots.
+    pub max_future_drift: Option<Duration>,
+    /// Eviction policy of the seen event IDs, when [`MemoryDatabaseOptions::max_events`] is reached (default: FIFO)
+    ///
+    /// This has effect only if events storing is disabled (see [`MemoryDatabaseOptions::events`])
+    /// and isn't persisted in the snapshots.
+    pub seen_eviction: EvictionPolicy,
+    /// Admission policy, consulted before saving an event (default: None)
+    ///
+    /// The events not admitted by the policy are rejected with [`RejectedReason::Policy`].
+    /// This isn't persisted in the snapshots.
+    pub admit_policy: Option<Arc<dyn DatabaseAdmitPolicy>>,
 }
 
 impl Default for MemoryDatabaseOptions {
@@ -36,6 +99,12 @@ impl Default for MemoryDatabaseOptions {
         Self {
             events: false,
             max_events: Some(MAX_EVENTS),
+            store_ephemeral: false,
+            kinds_whitelist: None,
+            max_age: None,
+            max_future_drift: None,
+            seen_eviction: EvictionPolicy::default(),
+            admit_policy: None,
         }
     }
 }
@@ -55,10 +124,38 @@ enum InnerMemoryDatabase {
     Full(DatabaseHelper),
 }
 
+/// Content of a [`MemoryDatabase`] snapshot
+#[cfg(feature = "snapshot")]
+#[derive(Serialize, Deserialize)]
+enum SnapshotContent {
+    /// Seen event IDs, from the most recent
+    Tracker(Vec<EventId>),
+    /// Stored events and tombstones of the deleted ones
+    Full {
+        events: Vec<Event>,
+        deleted_ids: Vec<EventId>,
+        deleted_coordinates: Vec<(Coordinate, Timestamp)>,
+    },
+}
+
+/// [`MemoryDatabase`] snapshot
+#[cfg(feature = "snapshot")]
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u8,
+    max_events: Option<usize>,
+    store_ephemeral: bool,
+    kinds_whitelist: Option<HashSet<Kind>>,
+    max_age: Option<Duration>,
+    content: SnapshotContent,
+}
+
 /// Memory Database (RAM)
 #[derive(Debug, Clone)]
 pub struct MemoryDatabase {
     inner: InnerMemoryDatabase,
+    opts: MemoryDatabaseOptions,
+    changes: broadcast::Sender<DatabaseChange>,
 }
 
 impl Default for MemoryDatabase {
@@ -82,10 +179,24 @@ impl MemoryDatabase {
 
         // Check if event storing is allowed
         let inner: InnerMemoryDatabase = if opts.events {
-            let helper: DatabaseHelper = match opts.max_events {
+            let mut helper: DatabaseHelper = match opts.max_events {
                 Some(max) => DatabaseHelper::bounded(max),
                 None => DatabaseHelper::unbounded(),
             };
+            helper = helper.store_ephemeral(opts.store_ephemeral);
+
+            if let Some(kinds) = opts.kinds_whitelist.clone() {
+                helper = helper.kinds_whitelist(kinds);
+            }
+
+            if let Some(max_age) = opts.max_age {
+                helper = helper.max_age(max_age);
+            }
+
+            if let Some(drift) = opts.max_future_drift {
+                helper = helper.max_future_drift(drift);
+            }
+
             InnerMemoryDatabase::Full(helper)
         } else {
             let cache: LruCache<EventId, ()> = match opts.max_events {
@@ -99,7 +210,175 @@ impl MemoryDatabase {
             InnerMemoryDatabase::Tracker(Arc::new(RwLock::new(cache)))
         };
 
-        Self { inner }
+        let (changes, ..) = broadcast::channel(CHANGES_CHANNEL_SIZE);
+
+        Self {
+            inner,
+            opts,
+            changes,
+        }
+    }
+
+    /// Write a snapshot of the database
+    ///
+    /// The snapshot contains the options, the stored events and the tombstones of the deleted ones
+    /// (or the seen event IDs, if events storing is disabled), encoded in a compact binary format.
+    /// Use [`MemoryDatabase::restore_from`] to restore the database.
+    #[cfg(feature = "snapshot")]
+    pub async fn snapshot_to<W>(&self, writer: W) -> Result<(), DatabaseError>
+    where
+        W: Write,
+    {
+        let content: SnapshotContent = match &self.inner {
+            InnerMemoryDatabase::Tracker(tracker) => {
+                let seen_event_ids = tracker.read().await;
+                SnapshotContent::Tracker(seen_event_ids.iter().map(|(id, ..)| *id).collect())
+            }
+            InnerMemoryDatabase::Full(helper) => {
+                let (deleted_ids, deleted_coordinates) = helper.tombstones().await;
+                SnapshotContent::Full {
+                    events: helper.query(Filter::new()).await.to_vec(),
+                    deleted_ids: deleted_ids.into_iter().collect(),
+                    deleted_coordinates: deleted_coordinates.into_iter().collect(),
+                }
+            }
+        };
+
+        let snapshot: Snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            max_events: self.opts.max_events,
+            store_ephemeral: self.opts.store_ephemeral,
+            kinds_whitelist: self.opts.kinds_whitelist.clone(),
+            max_age: self.opts.max_age,
+            content,
+        };
+
+        bincode::serialize_into(writer, &snapshot).map_err(DatabaseError::backend)
+    }
+
+    /// Restore a database from a snapshot
+    ///
+    /// See [`MemoryDatabase::snapshot_to`].
+    #[cfg(feature = "snapshot")]
+    pub async fn restore_from<R>(reader: R) -> Result<Self, DatabaseError>
+    where
+        R: Read,
+    {
+        let snapshot: Snapshot =
+            bincode::deserialize_from(reader).map_err(DatabaseError::backend)?;
+
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(DatabaseError::backend(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported snapshot version: {}", snapshot.version),
+            )));
+        }
+
+        let opts: MemoryDatabaseOptions = MemoryDatabaseOptions {
+            events: matches!(snapshot.content, SnapshotContent::Full { .. }),
+            max_events: snapshot.max_events,
+            store_ephemeral: snapshot.store_ephemeral,
+            kinds_whitelist: snapshot.kinds_whitelist,
+            max_age: snapshot.max_age,
+            max_future_drift: None,
+            seen_eviction: EvictionPolicy::default(),
+            admit_policy: None,
+        };
+        let db: Self = Self::with_opts(opts);
+
+        match (&db.inner, snapshot.content) {
+            (InnerMemoryDatabase::Tracker(tracker), SnapshotContent::Tracker(ids)) => {
+                let mut seen_event_ids = tracker.write().await;
+
+                // Restore the least recent first
+                for id in ids.into_iter().rev() {
+                    seen_event_ids.put(id, ());
+                }
+            }
+            (
+                InnerMemoryDatabase::Full(helper),
+                SnapshotContent::Full {
+                    events,
+                    deleted_ids,
+                    deleted_coordinates,
+                },
+            ) => {
+                helper
+                    .restore_tombstones(
+                        deleted_ids.into_iter().collect(),
+                        deleted_coordinates.into_iter().collect(),
+                    )
+                    .await;
+
+                let events: BTreeSet<Event> = events.into_iter().collect();
+                helper.bulk_load(events).await;
+            }
+            _ => unreachable!("database kind selected from the snapshot content"),
+        }
+
+        Ok(db)
+    }
+
+    /// Set a custom [`Clock`], used for the time-dependent checks (i.e. NIP-40 expiration)
+    ///
+    /// By default [`SystemClock`] is used.
+    /// This has effect only if events storing is enabled (see [`MemoryDatabaseOptions::events`]).
+    pub fn clock<C>(self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        match self.inner {
+            InnerMemoryDatabase::Tracker(..) => self,
+            InnerMemoryDatabase::Full(helper) => Self {
+                inner: InnerMemoryDatabase::Full(helper.clock(clock)),
+                opts: self.opts,
+                changes: self.changes,
+            },
+        }
+    }
+
+    /// Set an admission policy, consulted before saving an event
+    ///
+    /// Shortcut for [`MemoryDatabaseOptions::admit_policy`].
+    pub fn admit_policy<T>(mut self, policy: T) -> Self
+    where
+        T: DatabaseAdmitPolicy + 'static,
+    {
+        self.opts.admit_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Estimate the memory used by the database, in bytes
+    ///
+    /// Sum the approximate size of the stored events and the overhead of the indexes
+    /// (or of the seen event IDs, if events storing is disabled).
+    /// Useful to choose [`MemoryDatabaseOptions::max_events`] against a memory budget.
+    pub async fn estimated_bytes(&self) -> usize {
+        match &self.inner {
+            InnerMemoryDatabase::Tracker(lru) => {
+                let lru = lru.read().await;
+                // Each LRU entry holds the key, the value and the pointers of the linked list and map
+                lru.len() * (mem::size_of::<EventId>() + 4 * mem::size_of::<usize>())
+            }
+            InnerMemoryDatabase::Full(helper) => helper.estimated_bytes().await,
+        }
+    }
+
+    /// Get the deduplication statistics of the stored events
+    ///
+    /// Return `None` if events storing is disabled.
+    pub async fn dedup_stats(&self) -> Option<DedupStats> {
+        match &self.inner {
+            InnerMemoryDatabase::Tracker(..) => None,
+            InnerMemoryDatabase::Full(helper) => Some(helper.dedup_stats().await),
+        }
+    }
+
+    /// Notify a change to the subscribers, if any
+    #[inline]
+    fn notify(&self, change: DatabaseChange) {
+        // An error means that there are no receivers
+        let _ = self.changes.send(change);
     }
 }
 
@@ -115,22 +394,126 @@ impl NostrEventsDatabase for MemoryDatabase {
         event: &'a Event,
     ) -> BoxedFuture<'a, Result<SaveEventStatus, DatabaseError>> {
         Box::pin(async move {
+            // Check admission policy
+            if let Some(policy) = &self.opts.admit_policy {
+                if !policy.admit_event(event).await? {
+                    return Ok(SaveEventStatus::Rejected(RejectedReason::Policy));
+                }
+            }
+
             match &self.inner {
                 InnerMemoryDatabase::Tracker(tracker) => {
                     // Mark it as seen
                     let mut seen_event_ids = tracker.write().await;
-                    seen_event_ids.put(event.id, ());
+                    match self.opts.seen_eviction {
+                        EvictionPolicy::Fifo => {
+                            // Keep the position of an already seen ID
+                            if !seen_event_ids.contains(&event.id) {
+                                seen_event_ids.put(event.id, ());
+                            }
+                        }
+                        EvictionPolicy::Lru => {
+                            seen_event_ids.put(event.id, ());
+                        }
+                    }
 
                     Ok(SaveEventStatus::Success)
                 }
                 InnerMemoryDatabase::Full(helper) => {
-                    let DatabaseEventResult { status, .. } = helper.index_event(event).await;
+                    let DatabaseEventResult { status, to_discard } =
+                        helper.index_event(event).await;
+
+                    if status.is_success() {
+                        self.notify(DatabaseChange::Saved(Box::new(event.clone())));
+
+                        // Replaced events
+                        if !to_discard.is_empty() {
+                            self.notify(DatabaseChange::Deleted(to_discard));
+                        }
+                    }
+
                     Ok(status)
                 }
             }
         })
     }
 
+    fn save_events<'a>(
+        &'a self,
+        events: &'a [Event],
+    ) -> BoxedFuture<'a, Result<Vec<SaveEventStatus>, DatabaseError>> {
+        Box::pin(async move {
+            // Check admission policy
+            let mut admitted: Vec<bool> = Vec::with_capacity(events.len());
+            for event in events.iter() {
+                admitted.push(match &self.opts.admit_policy {
+                    Some(policy) => policy.admit_event(event).await?,
+                    None => true,
+                });
+            }
+
+            let mut statuses: Vec<SaveEventStatus> =
+                vec![SaveEventStatus::Rejected(RejectedReason::Policy); events.len()];
+
+            match &self.inner {
+                InnerMemoryDatabase::Tracker(tracker) => {
+                    // Mark them as seen
+                    let mut seen_event_ids = tracker.write().await;
+                    for ((event, admitted), status) in
+                        events.iter().zip(admitted).zip(statuses.iter_mut())
+                    {
+                        if !admitted {
+                            continue;
+                        }
+
+                        match self.opts.seen_eviction {
+                            EvictionPolicy::Fifo => {
+                                // Keep the position of an already seen ID
+                                if !seen_event_ids.contains(&event.id) {
+                                    seen_event_ids.put(event.id, ());
+                                }
+                            }
+                            EvictionPolicy::Lru => {
+                                seen_event_ids.put(event.id, ());
+                            }
+                        }
+
+                        *status = SaveEventStatus::Success;
+                    }
+                }
+                InnerMemoryDatabase::Full(helper) => {
+                    let to_index: Vec<(usize, &Event)> = events
+                        .iter()
+                        .enumerate()
+                        .zip(admitted)
+                        .filter_map(|(item, admitted)| admitted.then_some(item))
+                        .collect();
+
+                    let results: Vec<DatabaseEventResult> = helper
+                        .index_events(to_index.iter().map(|(.., event)| *event))
+                        .await;
+
+                    for ((index, event), DatabaseEventResult { status, to_discard }) in
+                        to_index.into_iter().zip(results)
+                    {
+                        if status.is_success() {
+                            self.notify(DatabaseChange::Saved(Box::new(event.clone())));
+
+                            // Replaced events
+                            if !to_discard.is_empty() {
+                                self.notify(DatabaseChange::Deleted(to_discard));
+                            }
+                        }
+
+                        statuses[index] = status;
+                    }
+                }
+            }
+
+            Ok(statuses)
+        })
+    }
+
     fn check_id<'a>(
         &'a self,
         event_id: &'a EventId,
@@ -138,9 +521,13 @@ impl NostrEventsDatabase for MemoryDatabase {
         Box::pin(async move {
             match &self.inner {
                 InnerMemoryDatabase::Tracker(tracker) => {
-                    let seen_event_ids = tracker.read().await;
+                    let seen: bool = match self.opts.seen_eviction {
+                        EvictionPolicy::Fifo => tracker.read().await.contains(event_id),
+                        // Mark it as recently used
+                        EvictionPolicy::Lru => tracker.write().await.get(event_id).is_some(),
+                    };
 
-                    Ok(if seen_event_ids.contains(event_id) {
+                    Ok(if seen {
                         DatabaseEventStatus::Saved
                     } else {
                         DatabaseEventStatus::NotExistent
@@ -186,6 +573,18 @@ impl NostrEventsDatabase for MemoryDatabase {
         })
     }
 
+    fn events_by_ids<'a>(
+        &'a self,
+        ids: &'a [EventId],
+    ) -> BoxedFuture<'a, Result<Vec<Event>, DatabaseError>> {
+        Box::pin(async move {
+            match &self.inner {
+                InnerMemoryDatabase::Tracker(..) => Ok(Vec::new()),
+                InnerMemoryDatabase::Full(helper) => Ok(helper.events_by_ids(ids).await),
+            }
+        })
+    }
+
     fn count(&self, filter: Filter) -> BoxedFuture<Result<usize, DatabaseError>> {
         Box::pin(async move {
             match &self.inner {
@@ -221,12 +620,42 @@ impl NostrEventsDatabase for MemoryDatabase {
             match &self.inner {
                 InnerMemoryDatabase::Tracker(..) => Ok(()),
                 InnerMemoryDatabase::Full(helper) => {
-                    helper.delete(filter).await;
+                    match helper.delete(filter).await {
+                        Some(ids) if ids.is_empty() => {}
+                        Some(ids) => self.notify(DatabaseChange::Deleted(ids)),
+                        None => self.notify(DatabaseChange::Wiped),
+                    }
+
                     Ok(())
                 }
             }
         })
     }
+
+    fn delete_by_ids<'a>(
+        &'a self,
+        ids: &'a [EventId],
+    ) -> BoxedFuture<'a, Result<usize, DatabaseError>> {
+        Box::pin(async move {
+            match &self.inner {
+                InnerMemoryDatabase::Tracker(..) => Ok(0),
+                InnerMemoryDatabase::Full(helper) => {
+                    let deleted: HashSet<EventId> = helper.delete_by_ids(ids).await;
+                    let count: usize = deleted.len();
+
+                    if !deleted.is_empty() {
+                        self.notify(DatabaseChange::Deleted(deleted));
+                    }
+
+                    Ok(count)
+                }
+            }
+        })
+    }
+
+    fn subscribe_changes(&self) -> broadcast::Receiver<DatabaseChange> {
+        self.changes.subscribe()
+    }
 }
 
 impl NostrDatabaseWipe for MemoryDatabase {
@@ -239,6 +668,7 @@ impl NostrDatabaseWipe for MemoryDatabase {
                 }
                 InnerMemoryDatabase::Full(helper) => {
                     helper.clear().await;
+                    self.notify(DatabaseChange::Wiped);
                 }
             }
 
@@ -246,3 +676,494 @@ impl NostrDatabaseWipe for MemoryDatabase {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct RejectTextNotes;
+
+    impl DatabaseAdmitPolicy for RejectTextNotes {
+        fn admit_event<'a>(
+            &'a self,
+            event: &'a Event,
+        ) -> BoxedFuture<'a, Result<bool, DatabaseError>> {
+            Box::pin(async move { Ok(event.kind != Kind::TextNote) })
+        }
+    }
+
+    async fn seen_eviction_db(policy: EvictionPolicy, events: &[Event]) -> MemoryDatabase {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            max_events: Some(3),
+            seen_eviction: policy,
+            ..Default::default()
+        });
+
+        for event in events.iter().take(3) {
+            db.save_event(event).await.unwrap();
+        }
+
+        // Re-access the first one, then overflow the capacity
+        db.check_id(&events[0].id).await.unwrap();
+        db.save_event(&events[3]).await.unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn test_seen_eviction_fifo() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..4)
+            .map(|i| {
+                EventBuilder::text_note(format!("Text note #{i}"))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        // FIFO is the default, LRU is opt-in
+        assert_eq!(
+            MemoryDatabaseOptions::default().seen_eviction,
+            EvictionPolicy::Fifo
+        );
+
+        let db = seen_eviction_db(EvictionPolicy::Fifo, &events).await;
+
+        // The first seen is evicted, despite the re-access
+        assert_eq!(
+            db.check_id(&events[0].id).await.unwrap(),
+            DatabaseEventStatus::NotExistent
+        );
+        for event in events.iter().skip(1) {
+            assert_eq!(
+                db.check_id(&event.id).await.unwrap(),
+                DatabaseEventStatus::Saved
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seen_eviction_lru() {
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..4)
+            .map(|i| {
+                EventBuilder::text_note(format!("Text note #{i}"))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        let db = seen_eviction_db(EvictionPolicy::Lru, &events).await;
+
+        // The re-accessed one is retained, the least recently used is evicted
+        assert_eq!(
+            db.check_id(&events[1].id).await.unwrap(),
+            DatabaseEventStatus::NotExistent
+        );
+        for id in [events[0].id, events[2].id, events[3].id] {
+            assert_eq!(db.check_id(&id).await.unwrap(), DatabaseEventStatus::Saved);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_with_tag_present() {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        });
+
+        let keys = Keys::generate();
+        let mut tagged: Vec<Event> = Vec::new();
+        for i in 0..6 {
+            let mut builder = EventBuilder::text_note(format!("Text note #{i}"))
+                .custom_created_at(Timestamp::from_secs(1_000 + i));
+
+            // Only the even ones have a `g` tag, with different values
+            if i % 2 == 0 {
+                builder = builder.tag(Tag::custom(
+                    TagKind::single_letter(Alphabet::G, false),
+                    [format!("u4pruy{i}")],
+                ));
+            }
+
+            let event = builder.sign_with_keys(&keys).unwrap();
+            db.save_event(&event).await.unwrap();
+
+            if i % 2 == 0 {
+                tagged.push(event);
+            }
+        }
+
+        let g = SingleLetterTag::lowercase(Alphabet::G);
+
+        let events = db
+            .query_with_tag_present(Filter::new().kind(Kind::TextNote), g)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(tagged.iter().all(|e| events.contains(e)));
+
+        // The limit is applied to the tagged events
+        let events = db
+            .query_with_tag_present(Filter::new().kind(Kind::TextNote).limit(2), g)
+            .await
+            .unwrap();
+        assert_eq!(events.to_vec(), vec![tagged[2].clone(), tagged[1].clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_kinds_whitelist() {
+        let opts = MemoryDatabaseOptions {
+            events: true,
+            kinds_whitelist: Some(HashSet::from([Kind::Metadata])),
+            ..Default::default()
+        };
+        let db = MemoryDatabase::with_opts(opts);
+
+        let keys = Keys::generate();
+        let metadata = EventBuilder::metadata(&Metadata::new().name("Key A"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let text_note = EventBuilder::text_note("Text note")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let status = db.save_event(&text_note).await.unwrap();
+        assert_eq!(
+            status,
+            SaveEventStatus::Rejected(RejectedReason::KindNotAllowed)
+        );
+        assert!(db.event_by_id(&text_note.id).await.unwrap().is_none());
+
+        let status = db.save_event(&metadata).await.unwrap();
+        assert_eq!(status, SaveEventStatus::Success);
+        assert_eq!(db.event_by_id(&metadata.id).await.unwrap(), Some(metadata));
+    }
+
+    #[tokio::test]
+    async fn test_max_future_drift() {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            max_future_drift: Some(Duration::from_secs(5 * 60)),
+            ..Default::default()
+        });
+
+        let keys = Keys::generate();
+        let future = EventBuilder::text_note("From the future")
+            .custom_created_at(Timestamp::now() + Duration::from_secs(60 * 60))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let status = db.save_event(&future).await.unwrap();
+        assert_eq!(
+            status,
+            SaveEventStatus::Rejected(RejectedReason::FutureDated)
+        );
+        assert!(db.event_by_id(&future.id).await.unwrap().is_none());
+
+        // Within the drift
+        let event = EventBuilder::text_note("Slightly in the future")
+            .custom_created_at(Timestamp::now() + Duration::from_secs(60))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let status = db.save_event(&event).await.unwrap();
+        assert_eq!(status, SaveEventStatus::Success);
+    }
+
+    #[tokio::test]
+    async fn test_admit_policy() {
+        let opts = MemoryDatabaseOptions {
+            events: true,
+            admit_policy: Some(Arc::new(RejectTextNotes)),
+            ..Default::default()
+        };
+        let db = MemoryDatabase::with_opts(opts);
+
+        let keys = Keys::generate();
+        let metadata = EventBuilder::metadata(&Metadata::new().name("Key A"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let text_note = EventBuilder::text_note("Text note")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let status = db.save_event(&text_note).await.unwrap();
+        assert_eq!(status, SaveEventStatus::Rejected(RejectedReason::Policy));
+        assert!(db.event_by_id(&text_note.id).await.unwrap().is_none());
+
+        let status = db.save_event(&metadata).await.unwrap();
+        assert_eq!(status, SaveEventStatus::Success);
+        assert_eq!(db.event_by_id(&metadata.id).await.unwrap(), Some(metadata));
+    }
+
+    #[tokio::test]
+    async fn test_save_events() {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            admit_policy: Some(Arc::new(RejectTextNotes)),
+            ..Default::default()
+        });
+
+        let keys = Keys::generate();
+        let metadata = EventBuilder::metadata(&Metadata::new().name("Key A"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let text_note = EventBuilder::text_note("Text note")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let reaction = EventBuilder::reaction(&text_note, "+")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let events = [
+            metadata.clone(),
+            text_note.clone(),
+            reaction.clone(),
+            metadata,
+        ];
+        let statuses = db.save_events(&events).await.unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                SaveEventStatus::Success,
+                SaveEventStatus::Rejected(RejectedReason::Policy),
+                SaveEventStatus::Success,
+                SaveEventStatus::Rejected(RejectedReason::Duplicate),
+            ]
+        );
+
+        assert_eq!(db.count(Filter::new()).await.unwrap(), 2);
+        assert!(db.event_by_id(&text_note.id).await.unwrap().is_none());
+        assert_eq!(db.event_by_id(&reaction.id).await.unwrap(), Some(reaction));
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_ids() {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        });
+
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..5)
+            .map(|i| {
+                EventBuilder::text_note(format!("Text note #{i}"))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        for event in events.iter() {
+            db.save_event(event).await.unwrap();
+        }
+
+        // Delete 3 events, plus an unknown ID
+        let ids: Vec<EventId> = vec![
+            events[0].id,
+            events[2].id,
+            events[4].id,
+            EventId::all_zeros(),
+        ];
+        assert_eq!(db.delete_by_ids(&ids).await.unwrap(), 3);
+
+        for id in ids.iter() {
+            assert!(db.event_by_id(id).await.unwrap().is_none());
+        }
+        assert_eq!(db.count(Filter::new()).await.unwrap(), 2);
+        assert!(db.event_by_id(&events[1].id).await.unwrap().is_some());
+        assert!(db.event_by_id(&events[3].id).await.unwrap().is_some());
+
+        // Already deleted
+        assert_eq!(db.delete_by_ids(&ids).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_stats() {
+        let db = MemoryDatabase::new();
+        assert!(db.dedup_stats().await.is_none());
+
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        });
+
+        let keys = Keys::generate();
+        let events: Vec<Event> = (0..4)
+            .map(|i| {
+                EventBuilder::text_note(format!("Text note #{i}"))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        // Save every event twice
+        for event in events.iter().chain(events.iter()) {
+            db.save_event(event).await.unwrap();
+        }
+
+        assert_eq!(
+            db.dedup_stats().await,
+            Some(DedupStats {
+                inserted: 4,
+                duplicates: 4,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimated_bytes() {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        });
+        assert_eq!(db.estimated_bytes().await, 0);
+
+        let keys = Keys::generate();
+        let mut last: usize = 0;
+
+        for i in 0..10 {
+            let event = EventBuilder::text_note(format!("Text note #{i}"))
+                .tag(Tag::hashtag("nostr"))
+                .sign_with_keys(&keys)
+                .unwrap();
+            db.save_event(&event).await.unwrap();
+
+            let size: usize = db.estimated_bytes().await;
+            assert!(size > last + event.content.len());
+            last = size;
+        }
+
+        // Tracker
+        let db = MemoryDatabase::new();
+        assert_eq!(db.estimated_bytes().await, 0);
+        let event = EventBuilder::text_note("Text note")
+            .sign_with_keys(&keys)
+            .unwrap();
+        db.save_event(&event).await.unwrap();
+        assert!(db.estimated_bytes().await > 0);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_changes() {
+        let db = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        });
+        let mut changes = db.subscribe_changes();
+
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Text note")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // Save
+        db.save_event(&event).await.unwrap();
+        assert_eq!(
+            changes.recv().await.unwrap(),
+            DatabaseChange::Saved(Box::new(event.clone()))
+        );
+
+        // Duplicate: no change
+        db.save_event(&event).await.unwrap();
+
+        // Delete
+        db.delete(Filter::new().id(event.id)).await.unwrap();
+        assert_eq!(
+            changes.recv().await.unwrap(),
+            DatabaseChange::Deleted(HashSet::from([event.id]))
+        );
+
+        // Wipe
+        db.wipe().await.unwrap();
+        assert_eq!(changes.recv().await.unwrap(), DatabaseChange::Wiped);
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "snapshot")]
+    async fn test_snapshot_restore() {
+        let opts = MemoryDatabaseOptions {
+            events: true,
+            max_events: Some(100),
+            ..Default::default()
+        };
+        let db = MemoryDatabase::with_opts(opts.clone());
+
+        let keys = Keys::generate();
+        let metadata = EventBuilder::metadata(&Metadata::new().name("Key A"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        db.save_event(&metadata).await.unwrap();
+        for i in 0..10 {
+            let event = EventBuilder::text_note(format!("Text note {i}"))
+                .tag(Tag::hashtag("nostr"))
+                .sign_with_keys(&keys)
+                .unwrap();
+            db.save_event(&event).await.unwrap();
+        }
+
+        // Delete an event by ID and an addressable event by coordinate
+        let note = EventBuilder::text_note("Deleted note")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let article = EventBuilder::long_form_text_note("Deleted article")
+            .tag(Tag::identifier("article"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        db.save_event(&note).await.unwrap();
+        db.save_event(&article).await.unwrap();
+        let coordinate =
+            Coordinate::new(Kind::LongFormTextNote, keys.public_key()).identifier("article");
+        let deletion = EventBuilder::delete(
+            EventDeletionRequest::new()
+                .id(note.id)
+                .coordinate(coordinate.clone()),
+        )
+        .sign_with_keys(&keys)
+        .unwrap();
+        db.save_event(&deletion).await.unwrap();
+
+        let mut buf: Vec<u8> = Vec::new();
+        db.snapshot_to(&mut buf).await.unwrap();
+
+        let restored = MemoryDatabase::restore_from(buf.as_slice()).await.unwrap();
+        assert_eq!(restored.opts.events, opts.events);
+        assert_eq!(restored.opts.max_events, opts.max_events);
+
+        // The deleted events aren't accepted again
+        assert_eq!(
+            restored.save_event(&note).await.unwrap(),
+            SaveEventStatus::Rejected(RejectedReason::Deleted)
+        );
+        assert!(restored
+            .has_coordinate_been_deleted(&coordinate.borrow(), &article.created_at)
+            .await
+            .unwrap());
+
+        let filters = [
+            Filter::new(),
+            Filter::new().kind(Kind::TextNote).limit(5),
+            Filter::new().author(keys.public_key()).kind(Kind::Metadata),
+            Filter::new().hashtag("nostr"),
+        ];
+        for filter in filters {
+            assert_eq!(
+                restored.query(filter.clone()).await.unwrap(),
+                db.query(filter).await.unwrap()
+            );
+        }
+
+        // Events tracker
+        let db = MemoryDatabase::new();
+        db.save_event(&metadata).await.unwrap();
+
+        let mut buf: Vec<u8> = Vec::new();
+        db.snapshot_to(&mut buf).await.unwrap();
+
+        let restored = MemoryDatabase::restore_from(buf.as_slice()).await.unwrap();
+        assert_eq!(
+            restored.check_id(&metadata.id).await.unwrap(),
+            DatabaseEventStatus::Saved
+        );
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Database admission policy
+
+use std::fmt;
+
+use nostr::util::BoxedFuture;
+use nostr::Event;
+
+use crate::DatabaseError;
+
+/// Database admission policy
+///
+/// Consulted before storing an event.
+pub trait DatabaseAdmitPolicy: fmt::Debug + Send + Sync {
+    /// Admit [`Event`]
+    ///
+    /// Returns `true` if the event can be stored.
+    fn admit_event<'a>(&'a self, event: &'a Event) -> BoxedFuture<'a, Result<bool, DatabaseError>>;
+}
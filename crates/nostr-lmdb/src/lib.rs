@@ -57,6 +57,18 @@ impl NostrEventsDatabase for NostrLMDB {
         })
     }
 
+    fn save_events<'a>(
+        &'a self,
+        events: &'a [Event],
+    ) -> BoxedFuture<'a, Result<Vec<SaveEventStatus>, DatabaseError>> {
+        Box::pin(async move {
+            self.db
+                .save_events(events)
+                .await
+                .map_err(DatabaseError::backend)
+        })
+    }
+
     fn check_id<'a>(
         &'a self,
         event_id: &'a EventId,
@@ -109,6 +121,17 @@ impl NostrEventsDatabase for NostrLMDB {
         })
     }
 
+    fn events_by_ids<'a>(
+        &'a self,
+        ids: &'a [EventId],
+    ) -> BoxedFuture<'a, Result<Vec<Event>, DatabaseError>> {
+        Box::pin(async move {
+            self.db
+                .get_events_by_ids(ids)
+                .map_err(DatabaseError::backend)
+        })
+    }
+
     fn count(&self, filter: Filter) -> BoxedFuture<Result<usize, DatabaseError>> {
         Box::pin(async move { self.db.count(filter).map_err(DatabaseError::backend) })
     }
@@ -266,6 +289,23 @@ mod tests {
         assert_eq!(db.count_all().await, added_events + 1)
     }
 
+    #[tokio::test]
+    async fn test_events_by_ids() {
+        let db = TempDatabase::new();
+
+        let _added_events: usize = db.add_random_events().await;
+
+        let (_keys, event_a) = db.add_event(EventBuilder::text_note("Test A")).await;
+        let (_keys, event_b) = db.add_event(EventBuilder::text_note("Test B")).await;
+        let missing = EventId::all_zeros();
+
+        let events = db
+            .events_by_ids(&[event_b.id, missing, event_a.id])
+            .await
+            .unwrap();
+        assert_eq!(events, vec![event_b, event_a]);
+    }
+
     #[tokio::test]
     async fn test_replaceable_event() {
         let db = TempDatabase::new();
@@ -452,6 +492,35 @@ mod tests {
         assert_eq!(db.count_all().await, 8);
     }
 
+    #[tokio::test]
+    async fn test_deletion_of_another_author_events() {
+        let db = TempDatabase::new();
+
+        let keys_a = Keys::generate();
+        let keys_b = Keys::generate();
+
+        let (note_a, _) = db
+            .add_event_with_keys(EventBuilder::text_note("Note A"), &keys_a)
+            .await;
+        let (note_b, _) = db
+            .add_event_with_keys(EventBuilder::text_note("Note B"), &keys_b)
+            .await;
+
+        // B tries to delete both its own note and the note of A
+        let (deletion, status) = db
+            .add_event_with_keys(
+                EventBuilder::delete(EventDeletionRequest::new().id(note_b.id).id(note_a.id)),
+                &keys_b,
+            )
+            .await;
+        assert_eq!(status, SaveEventStatus::Success);
+
+        // Only the note of B has been deleted
+        assert_eq!(db.event_by_id(&note_a.id).await.unwrap(), Some(note_a));
+        assert_eq!(db.event_by_id(&note_b.id).await.unwrap(), None);
+        assert_eq!(db.event_by_id(&deletion.id).await.unwrap(), Some(deletion));
+    }
+
     #[tokio::test]
     async fn test_delete_events_with_filter() {
         let db = TempDatabase::new();
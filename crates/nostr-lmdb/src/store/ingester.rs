@@ -6,6 +6,7 @@ use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
 
 use heed::RwTxn;
+use nostr::event::KindClass;
 use nostr::nips::nip01::Coordinate;
 use nostr::{Event, Kind, Timestamp};
 use nostr_database::{FlatBufferBuilder, RejectedReason, SaveEventStatus};
@@ -97,7 +98,7 @@ impl Ingester {
         event: Event,
         fbb: &mut FlatBufferBuilder,
     ) -> nostr::Result<SaveEventStatus, Error> {
-        if event.kind.is_ephemeral() {
+        if event.kind.classification() == KindClass::Ephemeral {
             return Ok(SaveEventStatus::Rejected(RejectedReason::Ephemeral));
         }
 
@@ -187,6 +188,7 @@ impl Ingester {
         if let Kind::EventDeletion = event.kind {
             let invalid: bool = self.handle_deletion_event(&mut txn, &event)?;
 
+            // Nothing to delete: all the references are owned by other authors
             if invalid {
                 txn.abort();
                 return Ok(SaveEventStatus::Rejected(RejectedReason::InvalidDelete));
@@ -202,15 +204,30 @@ impl Ingester {
         Ok(SaveEventStatus::Success)
     }
 
+    /// Apply a deletion event
+    ///
+    /// The references to the events and coordinates of other authors are ignored.
+    /// Return `true` if all the references are owned by other authors.
     fn handle_deletion_event(&self, txn: &mut RwTxn, event: &Event) -> nostr::Result<bool, Error> {
         // Acquire read txn
         let read_txn = self.db.read_txn()?;
 
+        let mut references: usize = 0;
+        let mut foreign: usize = 0;
+
         for id in event.tags.event_ids() {
+            references += 1;
+
             if let Some(target) = self.db.get_event_by_id(&read_txn, id.as_bytes())? {
                 // Author must match
                 if target.pubkey != event.pubkey.as_bytes() {
-                    return Ok(true);
+                    foreign += 1;
+                    tracing::warn!(
+                        id = %event.id,
+                        target = %id,
+                        "Deletion event references an event of another author: ignoring it."
+                    );
+                    continue;
                 }
 
                 // Mark as deleted and remove event
@@ -220,9 +237,17 @@ impl Ingester {
         }
 
         for coordinate in event.tags.coordinates() {
+            references += 1;
+
             // Author must match
             if coordinate.public_key != event.pubkey {
-                return Ok(true);
+                foreign += 1;
+                tracing::warn!(
+                    id = %event.id,
+                    target = %coordinate,
+                    "Deletion event references a coordinate of another author: ignoring it."
+                );
+                continue;
             }
 
             // Mark deleted
@@ -241,6 +266,6 @@ impl Ingester {
 
         read_txn.commit()?;
 
-        Ok(false)
+        Ok(references > 0 && foreign == references)
     }
 }
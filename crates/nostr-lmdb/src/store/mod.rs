@@ -64,6 +64,26 @@ impl Store {
         rx.await?
     }
 
+    /// Save a batch of events
+    ///
+    /// All the events are queued to the ingester before waiting for the replies.
+    pub async fn save_events(&self, events: &[Event]) -> Result<Vec<SaveEventStatus>, Error> {
+        let mut replies = Vec::with_capacity(events.len());
+
+        for event in events.iter() {
+            let (item, rx) = IngesterItem::with_feedback(event.clone());
+            self.ingester.send(item).map_err(|_| Error::MpscSend)?;
+            replies.push(rx);
+        }
+
+        let mut statuses: Vec<SaveEventStatus> = Vec::with_capacity(replies.len());
+        for rx in replies.into_iter() {
+            statuses.push(rx.await??);
+        }
+
+        Ok(statuses)
+    }
+
     /// Get an event by ID
     pub fn get_event_by_id(&self, id: &EventId) -> Result<Option<Event>, Error> {
         let txn = self.db.read_txn()?;
@@ -75,6 +95,19 @@ impl Store {
         Ok(event)
     }
 
+    /// Get events by IDs, using a single read transaction
+    pub fn get_events_by_ids(&self, ids: &[EventId]) -> Result<Vec<Event>, Error> {
+        let txn = self.db.read_txn()?;
+        let mut events: Vec<Event> = Vec::with_capacity(ids.len());
+        for id in ids.iter() {
+            if let Some(event) = self.db.get_event_by_id(&txn, id.as_bytes())? {
+                events.push(event.into_owned());
+            }
+        }
+        txn.commit()?;
+        Ok(events)
+    }
+
     /// Do we have an event
     pub fn has_event(&self, id: &EventId) -> Result<bool, Error> {
         let txn = self.db.read_txn()?;
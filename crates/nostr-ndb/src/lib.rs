@@ -10,40 +10,334 @@
 #![allow(clippy::mutable_key_type)] // TODO: remove when possible. Needed to suppress false positive for async_trait
 
 use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
 use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
 
 pub extern crate nostr;
 pub extern crate nostr_database as database;
 pub extern crate nostrdb;
 
+use async_utility::time;
 use nostr_database::prelude::*;
 use nostrdb::{
-    Config, Filter as NdbFilter, IngestMetadata, Ndb, NdbStrVariant, Note, QueryResult, Transaction,
+    Config, Filter as NdbFilter, IngestMetadata, Ndb, NdbStrVariant, Note, NoteKey, QueryResult,
+    Subscription, Transaction,
 };
 
-const MAX_RESULTS: i32 = 10_000;
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+const TOMBSTONES_FILE: &str = "tombstones";
+
+/// [`NdbDatabase`] options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NdbDatabaseOptions {
+    /// Max time to wait for a saved event to be ingested (default: 500 ms)
+    ///
+    /// `nostrdb` ingests the events in background,
+    /// so [`NostrEventsDatabase::save_event`] subscribes to the event to know the result of the ingestion.
+    /// The events with an invalid signature are rejected before being queued, without waiting.
+    pub ingestion_timeout: Duration,
+    /// Max number of events returned by a query (default: 10_000)
+    ///
+    /// The results exceeding this value are truncated. Use [`NdbDatabase::query_with_limit`] to override it for a single query.
+    pub max_results: usize,
+}
+
+impl Default for NdbDatabaseOptions {
+    fn default() -> Self {
+        Self {
+            ingestion_timeout: Duration::from_millis(500),
+            max_results: DEFAULT_MAX_RESULTS,
+        }
+    }
+}
+
+/// nostrdb configuration
+///
+/// The unset values fallback to the nostrdb defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NdbConfig {
+    map_size: Option<usize>,
+    ingester_threads: Option<i32>,
+}
+
+impl NdbConfig {
+    /// New default configuration
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Max size of the LMDB map, in bytes (default: nostrdb default)
+    ///
+    /// Increase it for large databases: the writes fail when the map is full.
+    #[inline]
+    pub fn map_size(mut self, bytes: usize) -> Self {
+        self.map_size = Some(bytes);
+        self
+    }
+
+    /// Number of threads used to ingest the events (default: nostrdb default)
+    #[inline]
+    pub fn ingester_threads(mut self, threads: i32) -> Self {
+        self.ingester_threads = Some(threads.max(1));
+        self
+    }
+
+    fn to_config(self) -> Config {
+        let mut config: Config = Config::new();
+
+        if let Some(map_size) = self.map_size {
+            config = config.set_mapsize(map_size);
+        }
+
+        if let Some(threads) = self.ingester_threads {
+            config = config.set_ingester_threads(threads);
+        }
+
+        config
+    }
+}
 
 // Wrap `Ndb` into `NdbDatabase` because only traits defined in the current crate can be implemented for types defined outside the crate!
 
 /// [`nostrdb`](https://github.com/damus-io/nostrdb) backend
+///
+/// # Deletion
+///
+/// `nostrdb` doesn't expose a primitive to delete notes,
+/// so the deleted events are kept in a tombstone set that masks them from the queries.
+///
+/// When opened with a path, the tombstones are persisted in the database directory and loaded on reopen.
+/// When built from an [`Ndb`] instance (i.e., `NdbDatabase::from(ndb)`), the tombstones are kept only in memory:
+/// the deleted events will be visible again in the next session.
 #[derive(Debug, Clone)]
 pub struct NdbDatabase {
     db: Ndb,
+    opts: NdbDatabaseOptions,
+    deleted: Arc<RwLock<HashSet<EventId>>>,
+    tombstones: Option<PathBuf>,
 }
 
 impl NdbDatabase {
     /// Open nostrdb
+    #[inline]
     pub fn open<P>(path: P) -> Result<Self, DatabaseError>
+    where
+        P: AsRef<str>,
+    {
+        Self::open_with_opts(path, NdbDatabaseOptions::default())
+    }
+
+    /// Open nostrdb with custom options
+    #[inline]
+    pub fn open_with_opts<P>(path: P, opts: NdbDatabaseOptions) -> Result<Self, DatabaseError>
+    where
+        P: AsRef<str>,
+    {
+        Self::open_with_config_and_opts(path, NdbConfig::default(), opts)
+    }
+
+    /// Open nostrdb with custom configuration (i.e., LMDB map size)
+    #[inline]
+    pub fn open_with_config<P>(path: P, config: NdbConfig) -> Result<Self, DatabaseError>
+    where
+        P: AsRef<str>,
+    {
+        Self::open_with_config_and_opts(path, config, NdbDatabaseOptions::default())
+    }
+
+    /// Open nostrdb with custom configuration and options
+    pub fn open_with_config_and_opts<P>(
+        path: P,
+        config: NdbConfig,
+        opts: NdbDatabaseOptions,
+    ) -> Result<Self, DatabaseError>
     where
         P: AsRef<str>,
     {
         let path: &str = path.as_ref();
-        let config = Config::new();
+        let config: Config = config.to_config();
+
+        let tombstones: PathBuf = Path::new(path).join(TOMBSTONES_FILE);
+        let deleted: HashSet<EventId> = load_tombstones(&tombstones)?;
 
         Ok(Self {
             db: Ndb::new(path, &config).map_err(DatabaseError::backend)?,
+            opts,
+            deleted: Arc::new(RwLock::new(deleted)),
+            tombstones: Some(tombstones),
         })
     }
+
+    fn has_note(&self, id: &EventId) -> Result<bool, DatabaseError> {
+        let txn = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
+        match self.db.get_note_by_id(&txn, id.as_bytes()) {
+            Ok(..) => Ok(true),
+            Err(nostrdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(DatabaseError::backend(e)),
+        }
+    }
+
+    fn is_deleted(&self, id: &[u8; 32]) -> bool {
+        let deleted = self.deleted.read().unwrap_or_else(PoisonError::into_inner);
+        deleted.contains(&EventId::from_byte_array(*id))
+    }
+
+    /// Query nostrdb, masking the deleted events
+    fn query_notes<'a>(
+        &self,
+        txn: &'a Transaction,
+        filter: &Filter,
+        max_results: usize,
+    ) -> Result<Vec<QueryResult<'a>>, DatabaseError> {
+        let deleted = self.deleted.read().unwrap_or_else(PoisonError::into_inner);
+
+        if deleted.is_empty() {
+            return ndb_query(&self.db, txn, filter, max_results);
+        }
+
+        // The deleted events are masked after the query:
+        // over-fetch by the number of tombstones, so the masked events don't reduce the results below the limit.
+        let limit: usize = filter
+            .limit
+            .map_or(max_results, |limit| limit.min(max_results));
+
+        let mut over_fetch: Filter = filter.clone();
+        if let Some(limit) = over_fetch.limit.as_mut() {
+            *limit = limit.saturating_add(deleted.len());
+        }
+
+        let mut res: Vec<QueryResult<'a>> = ndb_query(
+            &self.db,
+            txn,
+            &over_fetch,
+            max_results.saturating_add(deleted.len()),
+        )?;
+        res.retain(|r| !deleted.contains(&EventId::from_byte_array(*r.note.id())));
+        res.truncate(limit);
+
+        Ok(res)
+    }
+
+    /// Get the IDs of all the events matching the filter
+    ///
+    /// Page the query by [`NdbDatabaseOptions::max_results`], so the matches aren't truncated.
+    fn matching_ids(
+        &self,
+        txn: &Transaction,
+        filter: &Filter,
+    ) -> Result<HashSet<EventId>, DatabaseError> {
+        // A filter with a limit is already bounded
+        if let Some(limit) = filter.limit {
+            let res: Vec<QueryResult> = ndb_query(&self.db, txn, filter, limit)?;
+            return Ok(res
+                .into_iter()
+                .map(|r| EventId::from_byte_array(*r.note.id()))
+                .collect());
+        }
+
+        let page_size: usize = self.opts.max_results.max(1);
+        let mut ids: HashSet<EventId> = HashSet::new();
+        let mut page_filter: Filter = filter.clone();
+
+        loop {
+            let res: Vec<QueryResult> = ndb_query(&self.db, txn, &page_filter, page_size)?;
+            let fetched: usize = res.len();
+
+            let mut newest: u64 = u64::MIN;
+            let mut oldest: u64 = u64::MAX;
+            for r in res.into_iter() {
+                newest = newest.max(r.note.created_at());
+                oldest = oldest.min(r.note.created_at());
+                ids.insert(EventId::from_byte_array(*r.note.id()));
+            }
+
+            // Last page
+            if fetched < page_size {
+                break;
+            }
+
+            if newest == oldest {
+                // The whole page has the same `created_at`: get all the events at it
+                let mut same_timestamp: Filter = filter.clone();
+                same_timestamp.since = Some(Timestamp::from(oldest));
+                same_timestamp.until = Some(Timestamp::from(oldest));
+
+                let mut max_results: usize = page_size;
+                loop {
+                    max_results = max_results.saturating_mul(2);
+
+                    let res: Vec<QueryResult> =
+                        ndb_query(&self.db, txn, &same_timestamp, max_results)?;
+                    let fetched: usize = res.len();
+                    ids.extend(
+                        res.into_iter()
+                            .map(|r| EventId::from_byte_array(*r.note.id())),
+                    );
+
+                    if fetched < max_results {
+                        break;
+                    }
+                }
+
+                if oldest == 0 {
+                    break;
+                }
+
+                page_filter.until = Some(Timestamp::from(oldest - 1));
+            } else {
+                // The events at `oldest` may be split across the pages: query them again
+                page_filter.until = Some(Timestamp::from(oldest));
+            }
+        }
+
+        Ok(ids)
+    }
+
+    fn query_events(&self, filter: Filter, max_results: usize) -> Result<Events, DatabaseError> {
+        let txn: Transaction = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
+        let mut events: Events = Events::new(&filter);
+        let res: Vec<QueryResult> = self.query_notes(&txn, &filter, max_results)?;
+        events.extend(
+            res.into_iter()
+                .filter_map(|r| ndb_note_to_event(r.note).ok())
+                .map(|e| e.into_owned()),
+        );
+        Ok(events)
+    }
+
+    /// Query the events, returning up to `max_results` events
+    ///
+    /// Override the [`NdbDatabaseOptions::max_results`] for this query.
+    pub async fn query_with_limit(
+        &self,
+        filter: Filter,
+        max_results: usize,
+    ) -> Result<Events, DatabaseError> {
+        self.query_events(filter, max_results)
+    }
+
+    fn unsubscribe(&self, sub: Subscription) -> Result<(), DatabaseError> {
+        // `Ndb` is a shared handle: unsubscribe from a clone to not require `&mut self`
+        let mut db: Ndb = self.db.clone();
+        db.unsubscribe(sub).map_err(DatabaseError::backend)
+    }
+
+    fn ingest(&self, event: &Event) -> Result<(), DatabaseError> {
+        let msg = RelayMessage::Event {
+            subscription_id: Cow::Owned(SubscriptionId::new("ndb")),
+            event: Cow::Borrowed(event),
+        };
+        let json: String = msg.as_json();
+        self.db
+            .process_event_with(&json, IngestMetadata::new())
+            .map_err(DatabaseError::backend)
+    }
 }
 
 impl Deref for NdbDatabase {
@@ -62,7 +356,12 @@ impl DerefMut for NdbDatabase {
 
 impl From<Ndb> for NdbDatabase {
     fn from(db: Ndb) -> Self {
-        Self { db }
+        Self {
+            db,
+            opts: NdbDatabaseOptions::default(),
+            deleted: Arc::new(RwLock::new(HashSet::new())),
+            tombstones: None,
+        }
     }
 }
 
@@ -78,16 +377,54 @@ impl NostrEventsDatabase for NdbDatabase {
         event: &'a Event,
     ) -> BoxedFuture<'a, Result<SaveEventStatus, DatabaseError>> {
         Box::pin(async move {
-            let msg = RelayMessage::Event {
-                subscription_id: Cow::Owned(SubscriptionId::new("ndb")),
-                event: Cow::Borrowed(event),
-            };
-            let json: String = msg.as_json();
-            self.db
-                .process_event_with(&json, IngestMetadata::new())
+            if self.is_deleted(event.id.as_bytes()) {
+                return Ok(SaveEventStatus::Rejected(RejectedReason::Deleted));
+            }
+
+            if self.has_note(&event.id)? {
+                return Ok(SaveEventStatus::Rejected(RejectedReason::Duplicate));
+            }
+
+            // nostrdb silently drops the events with an invalid signature:
+            // reject them here, instead of waiting for an ingestion that will never happen.
+            if event.verify().is_err() {
+                return Ok(SaveEventStatus::Rejected(RejectedReason::Other));
+            }
+
+            // Subscribe before queueing the event, to be notified as soon as it's ingested
+            let filter: NdbFilter = ndb_filter_conversion(&Filter::new().id(event.id));
+            let sub: Subscription = self
+                .db
+                .subscribe(&[filter])
                 .map_err(DatabaseError::backend)?;
-            // TODO: shouldn't return a success since we don't know if the ingestion was successful or not.
-            Ok(SaveEventStatus::Success)
+
+            let ingested: Option<Result<Vec<NoteKey>, nostrdb::Error>> = match self.ingest(event) {
+                Ok(()) => {
+                    time::timeout(
+                        Some(self.opts.ingestion_timeout),
+                        self.db.wait_for_notes(sub, 1),
+                    )
+                    .await
+                }
+                Err(e) => {
+                    self.unsubscribe(sub)?;
+                    return Err(e);
+                }
+            };
+
+            self.unsubscribe(sub)?;
+
+            match ingested {
+                Some(Ok(keys)) if !keys.is_empty() => Ok(SaveEventStatus::Success),
+                // Timeout or subscription error: check the database one last time
+                _ => {
+                    if self.has_note(&event.id)? {
+                        Ok(SaveEventStatus::Success)
+                    } else {
+                        Ok(SaveEventStatus::Rejected(RejectedReason::Other))
+                    }
+                }
+            }
         })
     }
 
@@ -96,6 +433,10 @@ impl NostrEventsDatabase for NdbDatabase {
         event_id: &'a EventId,
     ) -> BoxedFuture<'a, Result<DatabaseEventStatus, DatabaseError>> {
         Box::pin(async move {
+            if self.is_deleted(event_id.as_bytes()) {
+                return Ok(DatabaseEventStatus::Deleted);
+            }
+
             let txn = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
             let res = self.db.get_note_by_id(&txn, event_id.as_bytes());
             Ok(if res.is_ok() {
@@ -119,6 +460,10 @@ impl NostrEventsDatabase for NdbDatabase {
         event_id: &'a EventId,
     ) -> BoxedFuture<'a, Result<Option<Event>, DatabaseError>> {
         Box::pin(async move {
+            if self.is_deleted(event_id.as_bytes()) {
+                return Ok(None);
+            }
+
             let txn: Transaction = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
             let res: Result<Note, nostrdb::Error> =
                 self.db.get_note_by_id(&txn, event_id.as_bytes());
@@ -133,23 +478,13 @@ impl NostrEventsDatabase for NdbDatabase {
     fn count(&self, filter: Filter) -> BoxedFuture<Result<usize, DatabaseError>> {
         Box::pin(async move {
             let txn: Transaction = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
-            let res: Vec<QueryResult> = ndb_query(&self.db, &txn, &filter)?;
+            let res: Vec<QueryResult> = self.query_notes(&txn, &filter, self.opts.max_results)?;
             Ok(res.len())
         })
     }
 
     fn query(&self, filter: Filter) -> BoxedFuture<Result<Events, DatabaseError>> {
-        Box::pin(async move {
-            let txn: Transaction = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
-            let mut events: Events = Events::new(&filter);
-            let res: Vec<QueryResult> = ndb_query(&self.db, &txn, &filter)?;
-            events.extend(
-                res.into_iter()
-                    .filter_map(|r| ndb_note_to_event(r.note).ok())
-                    .map(|e| e.into_owned()),
-            );
-            Ok(events)
-        })
+        Box::pin(async move { self.query_events(filter, self.opts.max_results) })
     }
 
     fn negentropy_items(
@@ -158,7 +493,7 @@ impl NostrEventsDatabase for NdbDatabase {
     ) -> BoxedFuture<Result<Vec<(EventId, Timestamp)>, DatabaseError>> {
         Box::pin(async move {
             let txn: Transaction = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
-            let res: Vec<QueryResult> = ndb_query(&self.db, &txn, &filter)?;
+            let res: Vec<QueryResult> = self.query_notes(&txn, &filter, self.opts.max_results)?;
             Ok(res
                 .into_iter()
                 .map(|r| ndb_note_to_neg_item(r.note))
@@ -166,8 +501,23 @@ impl NostrEventsDatabase for NdbDatabase {
         })
     }
 
-    fn delete(&self, _filter: Filter) -> BoxedFuture<Result<(), DatabaseError>> {
-        Box::pin(async move { Err(DatabaseError::NotSupported) })
+    fn delete(&self, filter: Filter) -> BoxedFuture<Result<(), DatabaseError>> {
+        Box::pin(async move {
+            let txn: Transaction = Transaction::new(&self.db).map_err(DatabaseError::backend)?;
+            let ids: HashSet<EventId> = self.matching_ids(&txn, &filter)?;
+
+            // nostrdb can't delete notes: mask them
+            let mut deleted = self.deleted.write().unwrap_or_else(PoisonError::into_inner);
+            let new: Vec<EventId> = ids.into_iter().filter(|id| !deleted.contains(id)).collect();
+
+            if let Some(path) = &self.tombstones {
+                append_tombstones(path, &new)?;
+            }
+
+            deleted.extend(new);
+
+            Ok(())
+        })
     }
 }
 
@@ -178,13 +528,42 @@ impl NostrDatabaseWipe for NdbDatabase {
     }
 }
 
+fn load_tombstones(path: &Path) -> Result<HashSet<EventId>, DatabaseError> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(bytes
+            .chunks_exact(EventId::LEN)
+            .filter_map(|id| EventId::from_slice(id).ok())
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(e) => Err(DatabaseError::backend(e)),
+    }
+}
+
+fn append_tombstones(path: &Path, ids: &[EventId]) -> Result<(), DatabaseError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let bytes: Vec<u8> = ids.iter().flat_map(|id| id.to_bytes()).collect();
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(DatabaseError::backend)?;
+    file.write_all(&bytes).map_err(DatabaseError::backend)?;
+    file.sync_data().map_err(DatabaseError::backend)
+}
+
 fn ndb_query<'a>(
     db: &Ndb,
     txn: &'a Transaction,
     filter: &Filter,
+    max_results: usize,
 ) -> Result<Vec<QueryResult<'a>>, DatabaseError> {
     let filter: nostrdb::Filter = ndb_filter_conversion(filter);
-    db.query(txn, &[filter], MAX_RESULTS)
+    let max_results: i32 = i32::try_from(max_results).unwrap_or(i32::MAX);
+    db.query(txn, &[filter], max_results)
         .map_err(DatabaseError::backend)
 }
 
@@ -264,3 +643,332 @@ fn ndb_note_to_neg_item(note: Note) -> (EventId, Timestamp) {
     let created_at = Timestamp::from_secs(note.created_at());
     (id, created_at)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// nostrdb ingests the events in background: wait until they are queryable
+    async fn wait_for_count(db: &NdbDatabase, filter: Filter, expected: usize) {
+        for _ in 0..100 {
+            if db.count(filter.clone()).await.unwrap() == expected {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        panic!("events not ingested");
+    }
+
+    #[tokio::test]
+    async fn test_save_event_status() {
+        let dir = TempDir::new().unwrap();
+        let db = NdbDatabase::open(dir.path().to_str().unwrap()).unwrap();
+
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Valid")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        assert_eq!(
+            db.save_event(&event).await.unwrap(),
+            SaveEventStatus::Success
+        );
+        assert_eq!(
+            db.save_event(&event).await.unwrap(),
+            SaveEventStatus::Rejected(RejectedReason::Duplicate)
+        );
+
+        // Use the signature of another event
+        let mut invalid = EventBuilder::text_note("Invalid")
+            .sign_with_keys(&keys)
+            .unwrap();
+        invalid.sig = event.sig;
+
+        // Rejected without waiting for the ingestion
+        let now = Instant::now();
+        assert_eq!(
+            db.save_event(&invalid).await.unwrap(),
+            SaveEventStatus::Rejected(RejectedReason::Other)
+        );
+        assert!(now.elapsed() < db.opts.ingestion_timeout);
+        assert_eq!(db.event_by_id(&invalid.id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_open_with_config() {
+        let dir = TempDir::new().unwrap();
+        let config = NdbConfig::new()
+            .map_size(64 * 1024 * 1024)
+            .ingester_threads(2);
+        let db = NdbDatabase::open_with_config(dir.path().to_str().unwrap(), config).unwrap();
+
+        let keys = Keys::generate();
+        for i in 0..100 {
+            let event = EventBuilder::text_note(format!("Event #{i}"))
+                .sign_with_keys(&keys)
+                .unwrap();
+            assert_eq!(
+                db.save_event(&event).await.unwrap(),
+                SaveEventStatus::Success
+            );
+        }
+
+        assert_eq!(db.count(Filter::new()).await.unwrap(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_write_past_map_size() {
+        // The nostrdb default map size (32 GiB) can't be filled in a test:
+        // check that the writes exceeding a small map are rejected and succeed with a larger map.
+        let keys = Keys::generate();
+        let content: String = "x".repeat(32 * 1024);
+        let events: Vec<Event> = (0..256)
+            .map(|i| {
+                EventBuilder::new(Kind::Custom(9999), format!("{i} {content}"))
+                    .sign_with_keys(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        // 8 MiB of events don't fit into a 2 MiB map
+        let dir = TempDir::new().unwrap();
+        let config = NdbConfig::new().map_size(2 * 1024 * 1024);
+        let db = NdbDatabase::open_with_config(dir.path().to_str().unwrap(), config).unwrap();
+
+        let mut rejected: bool = false;
+        for event in events.iter() {
+            if db.save_event(event).await.unwrap() != SaveEventStatus::Success {
+                rejected = true;
+                break;
+            }
+        }
+        assert!(rejected);
+
+        // The same events fit into a 64 MiB map
+        let dir = TempDir::new().unwrap();
+        let config = NdbConfig::new().map_size(64 * 1024 * 1024);
+        let db = NdbDatabase::open_with_config(dir.path().to_str().unwrap(), config).unwrap();
+
+        for event in events.iter() {
+            assert_eq!(
+                db.save_event(event).await.unwrap(),
+                SaveEventStatus::Success
+            );
+        }
+        assert_eq!(db.count(Filter::new()).await.unwrap(), events.len());
+    }
+
+    #[tokio::test]
+    async fn test_max_results() {
+        let dir = TempDir::new().unwrap();
+        let db = NdbDatabase::open(dir.path().to_str().unwrap()).unwrap();
+
+        let keys = Keys::generate();
+        for i in 0..12_000 {
+            let event = EventBuilder::text_note(format!("Event #{i}"))
+                .sign_with_keys(&keys)
+                .unwrap();
+            db.ingest(&event).unwrap();
+        }
+
+        // Wait for the ingestion
+        for _ in 0..600 {
+            if db
+                .query_with_limit(Filter::new(), 20_000)
+                .await
+                .unwrap()
+                .len()
+                == 12_000
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        // Default cap
+        assert_eq!(db.count(Filter::new()).await.unwrap(), DEFAULT_MAX_RESULTS);
+        assert_eq!(
+            db.query(Filter::new()).await.unwrap().len(),
+            DEFAULT_MAX_RESULTS
+        );
+
+        // Per query cap
+        assert_eq!(
+            db.query_with_limit(Filter::new(), 20_000)
+                .await
+                .unwrap()
+                .len(),
+            12_000
+        );
+        assert_eq!(
+            db.query_with_limit(Filter::new(), 500).await.unwrap().len(),
+            500
+        );
+
+        // Raised database cap
+        let db = NdbDatabase {
+            opts: NdbDatabaseOptions {
+                max_results: 20_000,
+                ..Default::default()
+            },
+            ..db
+        };
+        assert_eq!(db.count(Filter::new()).await.unwrap(), 12_000);
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_filter() {
+        let dir = TempDir::new().unwrap();
+        let db = NdbDatabase::open(dir.path().to_str().unwrap()).unwrap();
+
+        let keys = Keys::generate();
+        let other = Keys::generate();
+
+        let e1 = EventBuilder::text_note("First")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let e2 = EventBuilder::text_note("Second")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let e3 = EventBuilder::text_note("Other")
+            .sign_with_keys(&other)
+            .unwrap();
+
+        for event in [&e1, &e2, &e3] {
+            assert_eq!(
+                db.save_event(event).await.unwrap(),
+                SaveEventStatus::Success
+            );
+        }
+
+        wait_for_count(&db, Filter::new(), 3).await;
+
+        db.delete(Filter::new().author(keys.public_key))
+            .await
+            .unwrap();
+
+        let events = db.query(Filter::new()).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events.contains(&e3));
+
+        assert_eq!(
+            db.count(Filter::new().author(keys.public_key))
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(db.event_by_id(&e1.id).await.unwrap(), None);
+        assert_eq!(
+            db.check_id(&e2.id).await.unwrap(),
+            DatabaseEventStatus::Deleted
+        );
+
+        // A deleted event can't be saved again
+        assert_eq!(
+            db.save_event(&e1).await.unwrap(),
+            SaveEventStatus::Rejected(RejectedReason::Deleted)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_limit_with_deleted_events() {
+        let dir = TempDir::new().unwrap();
+        let db = NdbDatabase::open(dir.path().to_str().unwrap()).unwrap();
+
+        let keys = Keys::generate();
+        let mut events = Vec::new();
+        for i in 0..5 {
+            let event = EventBuilder::text_note(format!("Event #{i}"))
+                .custom_created_at(Timestamp::from(1000 + i))
+                .sign_with_keys(&keys)
+                .unwrap();
+            assert_eq!(
+                db.save_event(&event).await.unwrap(),
+                SaveEventStatus::Success
+            );
+            events.push(event);
+        }
+
+        // Delete the 2 newest events
+        db.delete(Filter::new().ids([events[4].id, events[3].id]))
+            .await
+            .unwrap();
+
+        // The masked events don't reduce the results below the limit
+        let res = db.query(Filter::new().limit(3)).await.unwrap();
+        assert_eq!(res.len(), 3);
+        for event in events[..3].iter() {
+            assert!(res.contains(event));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_more_than_max_results() {
+        let dir = TempDir::new().unwrap();
+        let opts = NdbDatabaseOptions {
+            max_results: 3,
+            ..Default::default()
+        };
+        let db = NdbDatabase::open_with_opts(dir.path().to_str().unwrap(), opts).unwrap();
+
+        let keys = Keys::generate();
+        for i in 0..10 {
+            // Some events share the same timestamp
+            let event = EventBuilder::text_note(format!("Event #{i}"))
+                .custom_created_at(Timestamp::from(1000 + i / 4))
+                .sign_with_keys(&keys)
+                .unwrap();
+            assert_eq!(
+                db.save_event(&event).await.unwrap(),
+                SaveEventStatus::Success
+            );
+        }
+
+        db.delete(Filter::new().author(keys.public_key))
+            .await
+            .unwrap();
+
+        assert_eq!(db.deleted.read().unwrap().len(), 10);
+        assert!(db.query(Filter::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tombstones_persisted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let keys = Keys::generate();
+        let deleted = EventBuilder::text_note("Deleted")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let kept = EventBuilder::text_note("Kept")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        {
+            let db = NdbDatabase::open(path).unwrap();
+            for event in [&deleted, &kept] {
+                assert_eq!(
+                    db.save_event(event).await.unwrap(),
+                    SaveEventStatus::Success
+                );
+            }
+
+            db.delete(Filter::new().id(deleted.id)).await.unwrap();
+        }
+
+        // Reopen
+        let db = NdbDatabase::open(path).unwrap();
+        assert_eq!(db.event_by_id(&deleted.id).await.unwrap(), None);
+        assert_eq!(
+            db.check_id(&deleted.id).await.unwrap(),
+            DatabaseEventStatus::Deleted
+        );
+        assert_eq!(db.event_by_id(&kept.id).await.unwrap(), Some(kept));
+    }
+}
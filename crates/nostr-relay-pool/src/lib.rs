@@ -27,16 +27,21 @@ pub mod relay;
 mod shared;
 pub mod stream;
 pub mod transport;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod verification;
 
 pub use self::pool::options::RelayPoolOptions;
 pub use self::pool::{Output, RelayPool, RelayPoolNotification};
 pub use self::relay::flags::{AtomicRelayServiceFlags, RelayServiceFlags};
 pub use self::relay::limits::RelayLimits;
 pub use self::relay::options::{
-    RelayOptions, SubscribeAutoCloseOptions, SubscribeOptions, SyncDirection, SyncOptions,
+    RelayOptions, RelayQueuePolicy, SubscribeAutoCloseOptions, SubscribeOptions, SyncDirection,
+    SyncOptions,
 };
 pub use self::relay::stats::RelayConnectionStats;
 pub use self::relay::{Reconciliation, Relay, RelayNotification, RelayStatus};
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::verification::VerificationPool;
 
 // Not public API.
 #[doc(hidden)]
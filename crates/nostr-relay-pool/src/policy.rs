@@ -4,10 +4,12 @@
 
 //! Policies
 
+use std::collections::HashSet;
 use std::fmt;
 
 use nostr::util::BoxedFuture;
-use nostr::{Event, RelayUrl, SubscriptionId};
+use nostr::{Event, EventId, Kind, PublicKey, RelayUrl, SubscriptionId};
+use tokio::sync::RwLock;
 
 /// Policy Error
 #[derive(Debug)]
@@ -97,8 +99,164 @@ pub trait AdmitPolicy: fmt::Debug + Send + Sync {
     }
 }
 
+/// Blocklist admission policy
+///
+/// Reject the events with a blocked ID or authored by a blocked public key.
+/// The blocklist can be updated at runtime.
+#[derive(Debug, Default)]
+pub struct BlocklistPolicy {
+    ids: RwLock<HashSet<EventId>>,
+    public_keys: RwLock<HashSet<PublicKey>>,
+}
+
+impl BlocklistPolicy {
+    /// New empty blocklist
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// New blocklist with event IDs and public keys
+    pub fn with_blocked<I, P>(ids: I, public_keys: P) -> Self
+    where
+        I: IntoIterator<Item = EventId>,
+        P: IntoIterator<Item = PublicKey>,
+    {
+        Self {
+            ids: RwLock::new(ids.into_iter().collect()),
+            public_keys: RwLock::new(public_keys.into_iter().collect()),
+        }
+    }
+
+    /// Block an event ID
+    ///
+    /// Returns `false` if it was already blocked.
+    pub async fn block_event(&self, id: EventId) -> bool {
+        let mut ids = self.ids.write().await;
+        ids.insert(id)
+    }
+
+    /// Unblock an event ID
+    ///
+    /// Returns `false` if it wasn't blocked.
+    pub async fn unblock_event(&self, id: &EventId) -> bool {
+        let mut ids = self.ids.write().await;
+        ids.remove(id)
+    }
+
+    /// Block a public key
+    ///
+    /// Returns `false` if it was already blocked.
+    pub async fn block_public_key(&self, public_key: PublicKey) -> bool {
+        let mut public_keys = self.public_keys.write().await;
+        public_keys.insert(public_key)
+    }
+
+    /// Unblock a public key
+    ///
+    /// Returns `false` if it wasn't blocked.
+    pub async fn unblock_public_key(&self, public_key: &PublicKey) -> bool {
+        let mut public_keys = self.public_keys.write().await;
+        public_keys.remove(public_key)
+    }
+
+    /// Check if an event ID is blocked
+    pub async fn is_event_blocked(&self, id: &EventId) -> bool {
+        let ids = self.ids.read().await;
+        ids.contains(id)
+    }
+
+    /// Check if a public key is blocked
+    pub async fn is_public_key_blocked(&self, public_key: &PublicKey) -> bool {
+        let public_keys = self.public_keys.read().await;
+        public_keys.contains(public_key)
+    }
+}
+
+impl AdmitPolicy for BlocklistPolicy {
+    fn admit_event<'a>(
+        &'a self,
+        _relay_url: &'a RelayUrl,
+        _subscription_id: &'a SubscriptionId,
+        event: &'a Event,
+    ) -> BoxedFuture<'a, Result<AdmitStatus, PolicyError>> {
+        Box::pin(async move {
+            if self.is_public_key_blocked(&event.pubkey).await {
+                return Ok(AdmitStatus::rejected("blocked author"));
+            }
+
+            if self.is_event_blocked(&event.id).await {
+                return Ok(AdmitStatus::rejected("blocked event"));
+            }
+
+            Ok(AdmitStatus::Success)
+        })
+    }
+}
+
+/// Kind filter admission policy
+///
+/// Admit only the allowed kinds or reject the blocked ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KindFilterPolicy {
+    /// Admit only these kinds
+    Allow(HashSet<Kind>),
+    /// Reject these kinds
+    Block(HashSet<Kind>),
+}
+
+impl KindFilterPolicy {
+    /// Admit only the specified kinds
+    #[inline]
+    pub fn allow<I>(kinds: I) -> Self
+    where
+        I: IntoIterator<Item = Kind>,
+    {
+        Self::Allow(kinds.into_iter().collect())
+    }
+
+    /// Reject the specified kinds
+    #[inline]
+    pub fn block<I>(kinds: I) -> Self
+    where
+        I: IntoIterator<Item = Kind>,
+    {
+        Self::Block(kinds.into_iter().collect())
+    }
+
+    /// Check if a kind is admitted
+    pub fn is_admitted(&self, kind: &Kind) -> bool {
+        match self {
+            Self::Allow(kinds) => kinds.contains(kind),
+            Self::Block(kinds) => !kinds.contains(kind),
+        }
+    }
+}
+
+impl AdmitPolicy for KindFilterPolicy {
+    fn admit_event<'a>(
+        &'a self,
+        _relay_url: &'a RelayUrl,
+        _subscription_id: &'a SubscriptionId,
+        event: &'a Event,
+    ) -> BoxedFuture<'a, Result<AdmitStatus, PolicyError>> {
+        Box::pin(async move {
+            if self.is_admitted(&event.kind) {
+                Ok(AdmitStatus::Success)
+            } else {
+                Ok(AdmitStatus::rejected(format!(
+                    "kind {} not allowed",
+                    event.kind
+                )))
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use nostr::{EventBuilder, Keys};
+
     use super::*;
 
     #[test]
@@ -117,4 +275,93 @@ mod tests {
             }
         );
     }
+
+    #[tokio::test]
+    async fn test_blocklist_policy() {
+        let relay_url = RelayUrl::parse("wss://relay.damus.io").unwrap();
+        let sub_id = SubscriptionId::new("test");
+
+        let blocked_keys = Keys::generate();
+        let keys = Keys::generate();
+
+        let blocked_author = EventBuilder::text_note("Blocked author")
+            .sign_with_keys(&blocked_keys)
+            .unwrap();
+        let blocked_event = EventBuilder::text_note("Blocked event")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let admitted = EventBuilder::text_note("Admitted")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let policy = BlocklistPolicy::with_blocked([blocked_event.id], []);
+        assert!(policy.block_public_key(blocked_keys.public_key()).await);
+        assert!(!policy.block_public_key(blocked_keys.public_key()).await);
+
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &blocked_author)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::rejected("blocked author"));
+
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &blocked_event)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::rejected("blocked event"));
+
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &admitted)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::Success);
+
+        // Runtime update
+        assert!(policy.unblock_public_key(&blocked_keys.public_key()).await);
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &blocked_author)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::Success);
+    }
+
+    #[tokio::test]
+    async fn test_kind_filter_policy() {
+        let relay_url = RelayUrl::parse("wss://relay.damus.io").unwrap();
+        let sub_id = SubscriptionId::new("test");
+
+        let keys = Keys::generate();
+        let article = EventBuilder::long_form_text_note("Article")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let note = EventBuilder::text_note("Note")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // Allow-list
+        let policy = KindFilterPolicy::allow([Kind::LongFormTextNote]);
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &article)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::Success);
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &note)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::rejected("kind 1 not allowed"));
+
+        // Block-list
+        let policy = KindFilterPolicy::block([Kind::LongFormTextNote]);
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &article)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::rejected("kind 30023 not allowed"));
+        let status = policy
+            .admit_event(&relay_url, &sub_id, &note)
+            .await
+            .unwrap();
+        assert_eq!(status, AdmitStatus::Success);
+    }
 }
@@ -6,6 +6,7 @@
 
 use std::sync::Arc;
 
+use nostr::types::{Clock, SystemClock};
 use nostr::NostrSigner;
 use nostr_database::{MemoryDatabase, NostrDatabase};
 
@@ -26,6 +27,8 @@ pub struct RelayPoolBuilder {
     pub monitor: Option<Monitor>,
     /// Relay pool options
     pub opts: RelayPoolOptions,
+    /// Clock
+    pub clock: Arc<dyn Clock>,
     // Private stuff
     #[doc(hidden)]
     pub __database: Arc<dyn NostrDatabase>,
@@ -40,6 +43,7 @@ impl Default for RelayPoolBuilder {
             admit_policy: None,
             monitor: None,
             opts: RelayPoolOptions::default(),
+            clock: Arc::new(SystemClock),
             __database: Arc::new(MemoryDatabase::default()),
             __signer: None,
         }
@@ -87,6 +91,18 @@ impl RelayPoolBuilder {
         self
     }
 
+    /// Set a custom [`Clock`], used for the time-dependent checks (i.e. NIP-40 expiration)
+    ///
+    /// By default [`SystemClock`] is used.
+    #[inline]
+    pub fn clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Arc::new(clock);
+        self
+    }
+
     /// Build relay pool
     #[inline]
     pub fn build(self) -> RelayPool {
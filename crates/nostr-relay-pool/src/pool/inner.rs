@@ -48,15 +48,24 @@ impl InnerRelayPool {
     pub(super) fn from_builder(builder: RelayPoolBuilder) -> Self {
         let (notification_sender, _) = broadcast::channel(builder.opts.notification_channel_size);
 
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut state: SharedState = SharedState::new(
+            builder.__database,
+            builder.websocket_transport,
+            builder.__signer,
+            builder.admit_policy,
+            builder.opts.nip42_auto_authentication,
+            builder.monitor,
+            builder.clock,
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(workers) = builder.opts.verification_workers {
+            state = state.with_verification_workers(workers);
+        }
+
         Self {
-            state: SharedState::new(
-                builder.__database,
-                builder.websocket_transport,
-                builder.__signer,
-                builder.admit_policy,
-                builder.opts.nip42_auto_authentication,
-                builder.monitor,
-            ),
+            state,
             atomic: Arc::new(AtomicPrivateData {
                 relays: RwLock::new(HashMap::new()),
                 subscriptions: RwLock::new(HashMap::new()),
@@ -34,7 +34,7 @@ use crate::relay::options::{RelayOptions, ReqExitPolicy, SyncOptions};
 use crate::relay::Relay;
 use crate::shared::SharedState;
 use crate::stream::ReceiverStream;
-use crate::{Reconciliation, RelayServiceFlags, SubscribeOptions};
+use crate::{Reconciliation, RelayServiceFlags, RelayStatus, SubscribeOptions};
 
 /// Relay Pool Notification
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -68,6 +68,15 @@ pub enum RelayPoolNotification {
         /// The received relay message.
         message: RelayMessage<'static>,
     },
+    /// Relay status changed
+    ///
+    /// This notification is sent on every status transition of the relays in the pool.
+    RelayStatus {
+        /// Relay URL
+        relay_url: RelayUrl,
+        /// New status
+        status: RelayStatus,
+    },
     /// Shutdown
     ///
     /// This notification variant is sent after [`RelayPool::shutdown`] method is called and all connections have been closed.
@@ -1221,8 +1230,15 @@ impl RelayPool {
                 ));
             }
 
-            // Join all futures
-            let list = future::join_all(futures).await;
+            // Join all futures, stopping if the stream has been dropped (i.e., the fetch has been cancelled).
+            // Dropping the futures closes the subscriptions.
+            let list = tokio::select! {
+                list = future::join_all(futures) => list,
+                _ = tx.closed() => {
+                    tracing::debug!("Events stream dropped, closing the subscriptions.");
+                    return;
+                }
+            };
 
             // Iter results
             for (url, result) in urls.into_iter().zip(list.into_iter()) {
@@ -1315,6 +1331,43 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_relay_status_notifications() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        let pool = RelayPool::default();
+        let mut notifications = pool.notifications();
+
+        pool.add_relay(&url, RelayOptions::default()).await.unwrap();
+        pool.connect().await;
+
+        let mut statuses: Vec<RelayStatus> = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Ok(notification) = notifications.recv().await {
+                if let RelayPoolNotification::RelayStatus { relay_url, status } = notification {
+                    assert_eq!(relay_url, url);
+                    statuses.push(status);
+
+                    if status.is_connected() {
+                        break;
+                    }
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            statuses,
+            vec![
+                RelayStatus::Pending,
+                RelayStatus::Connecting,
+                RelayStatus::Connected
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_remove_nonexistent_relay() {
         let pool = RelayPool::default();
@@ -12,6 +12,7 @@ pub struct RelayPoolOptions {
     pub(super) max_relays: Option<usize>,
     pub(super) nip42_auto_authentication: bool,
     pub(super) notification_channel_size: usize,
+    pub(super) verification_workers: Option<usize>,
 }
 
 impl Default for RelayPoolOptions {
@@ -20,6 +21,7 @@ impl Default for RelayPoolOptions {
             max_relays: None,
             nip42_auto_authentication: true,
             notification_channel_size: DEFAULT_NOTIFICATION_CHANNEL_SIZE,
+            verification_workers: None,
         }
     }
 }
@@ -53,4 +55,17 @@ impl RelayPoolOptions {
         self.notification_channel_size = size;
         self
     }
+
+    /// Verify the received events using a bounded pool of blocking workers (default: None)
+    ///
+    /// By default, the events are verified directly in the async tasks of the relays.
+    /// With a firehose of events, the signature verification may starve the executor:
+    /// set the max number of concurrent verification workers to offload it.
+    ///
+    /// Ignored on WASM.
+    #[inline]
+    pub fn verification_workers(mut self, workers: Option<usize>) -> Self {
+        self.verification_workers = workers;
+        self
+    }
 }
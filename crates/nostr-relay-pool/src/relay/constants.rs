@@ -14,6 +14,9 @@ pub(super) const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(60);
 /// Relay default notification channel size
 pub const DEFAULT_NOTIFICATION_CHANNEL_SIZE: usize = 2048;
 
+/// Relay default outgoing message queue size
+pub const DEFAULT_OUTGOING_QUEUE_SIZE: usize = 1024;
+
 /// Max relay size
 pub const MAX_MESSAGE_SIZE: u32 = 5 * 1024 * 1024; // 5 MB
 /// Max event size
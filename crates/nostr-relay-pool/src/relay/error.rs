@@ -53,6 +53,8 @@ pub enum Error {
         /// Name of channel
         channel: String,
     },
+    /// Outgoing message queue is full
+    OutgoingQueueFull,
     /// Relay not ready
     NotReady,
     /// Relay not connected
@@ -116,6 +118,8 @@ pub enum Error {
     AuthenticationFailed,
     /// Premature exit
     PrematureExit,
+    /// Verification worker failed
+    VerificationWorkerFailed,
 }
 
 impl std::error::Error for Error {}
@@ -142,6 +146,7 @@ impl fmt::Display for Error {
             Self::CantSendChannelMessage { channel } => {
                 write!(f, "can't send message to the '{channel}' channel")
             }
+            Self::OutgoingQueueFull => write!(f, "outgoing message queue is full"),
             Self::NotReady => write!(f, "relay is initialized but not ready"),
             Self::NotConnected => write!(f, "relay not connected"),
             Self::Banned => write!(f, "relay banned"),
@@ -179,6 +184,7 @@ impl fmt::Display for Error {
             ),
             Self::AuthenticationFailed => write!(f, "authentication failed"),
             Self::PrematureExit => write!(f, "premature exit"),
+            Self::VerificationWorkerFailed => write!(f, "verification worker failed"),
         }
     }
 }
@@ -6,8 +6,7 @@ use std::borrow::Cow;
 use std::cmp;
 use std::collections::{HashMap, HashSet};
 #[cfg(feature = "nip11")]
-use std::sync::atomic::AtomicU64;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -18,6 +17,7 @@ use atomic_destructor::AtomicDestroyer;
 use negentropy::{Id, Negentropy, NegentropyStorageVector};
 use nostr::secp256k1::rand::{self, Rng};
 use nostr_database::prelude::*;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{broadcast, Mutex, MutexGuard, Notify, RwLock, RwLockWriteGuard};
 
@@ -27,7 +27,9 @@ use super::constants::{
     NEGENTROPY_LOW_WATER_UP, PING_INTERVAL, WAIT_FOR_OK_TIMEOUT, WEBSOCKET_TX_TIMEOUT,
 };
 use super::flags::AtomicRelayServiceFlags;
-use super::options::{RelayOptions, ReqExitPolicy, SubscribeAutoCloseOptions, SyncOptions};
+use super::options::{
+    RelayOptions, RelayQueuePolicy, ReqExitPolicy, SubscribeAutoCloseOptions, SyncOptions,
+};
 use super::ping::PingTracker;
 use super::stats::RelayConnectionStats;
 use super::{
@@ -38,7 +40,7 @@ use crate::policy::AdmitStatus;
 use crate::pool::RelayPoolNotification;
 use crate::relay::status::AtomicRelayStatus;
 use crate::shared::SharedState;
-use crate::transport::websocket::{BoxSink, BoxStream};
+use crate::transport::websocket::{BoxSink, BoxStream, WebSocketLimits};
 
 type ClientMessageJson = String;
 
@@ -67,8 +69,8 @@ struct RelayChannels {
 }
 
 impl RelayChannels {
-    pub fn new() -> Self {
-        let (tx_nostr, rx_nostr) = mpsc::channel(1024);
+    pub fn new(queue_size: usize) -> Self {
+        let (tx_nostr, rx_nostr) = mpsc::channel(queue_size);
 
         Self {
             nostr: (tx_nostr, Mutex::new(rx_nostr)),
@@ -82,12 +84,31 @@ impl RelayChannels {
         let msgs: Vec<ClientMessageJson> = msgs.into_iter().map(|msg| msg.as_json()).collect();
 
         // Send
-        self.nostr
-            .0
-            .try_send(msgs)
-            .map_err(|_| Error::CantSendChannelMessage {
+        self.nostr.0.try_send(msgs).map_err(|e| match e {
+            TrySendError::Full(..) => Error::OutgoingQueueFull,
+            TrySendError::Closed(..) => Error::CantSendChannelMessage {
                 channel: String::from("nostr"),
-            })
+            },
+        })
+    }
+
+    /// Send the messages, waiting up to `timeout` for free space in the queue
+    pub async fn send_client_msgs_wait(
+        &self,
+        msgs: Vec<ClientMessage<'_>>,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        // Serialize messages to JSON
+        let msgs: Vec<ClientMessageJson> = msgs.into_iter().map(|msg| msg.as_json()).collect();
+
+        // Send
+        match time::timeout(Some(timeout), self.nostr.0.send(msgs)).await {
+            Some(Ok(())) => Ok(()),
+            Some(Err(..)) => Err(Error::CantSendChannelMessage {
+                channel: String::from("nostr"),
+            }),
+            None => Err(Error::OutgoingQueueFull),
+        }
     }
 
     #[inline]
@@ -110,10 +131,23 @@ impl RelayChannels {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct SubscriptionData {
     pub filter: Filter,
     pub subscribed_at: Timestamp,
+    /// Newest `created_at` of the received events that match the filter (`0` if none)
+    ///
+    /// Used as `since` at re-subscription.
+    /// Atomic, to be updated without the subscriptions write lock.
+    pub last_event_at: AtomicU64,
+    /// Newest `created_at` of the stored events received before EOSE, in the current session (`0` if none)
+    ///
+    /// The relays send the stored events from the newest,
+    /// so it's committed to `last_event_at` only after EOSE:
+    /// if the connection drops before, the older stored events haven't been received yet.
+    pub stored_event_at: AtomicU64,
+    /// EOSE received in the current session
+    pub eose: AtomicBool,
     /// Subscription closed by relay
     pub closed: bool,
 }
@@ -124,11 +158,71 @@ impl Default for SubscriptionData {
             // TODO: use `Option<Filter>`?
             filter: Filter::new(),
             subscribed_at: Timestamp::zero(),
+            last_event_at: AtomicU64::new(0),
+            stored_event_at: AtomicU64::new(0),
+            eose: AtomicBool::new(false),
             closed: false,
         }
     }
 }
 
+impl SubscriptionData {
+    /// Track the newest `created_at` of the received events
+    fn event_received(&self, event: &Event) {
+        let created_at: u64 = event.created_at.as_u64();
+
+        // Before EOSE, track the stored events separately
+        let newest: &AtomicU64 = if self.eose.load(Ordering::SeqCst) {
+            &self.last_event_at
+        } else {
+            &self.stored_event_at
+        };
+
+        // Skip the filter matching if the event isn't newer
+        if created_at <= newest.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // Ignore the events that don't match the filter:
+        // a misbehaving relay may cause to skip the valid ones at re-subscription.
+        if self.filter.match_event(event) {
+            newest.fetch_max(created_at, Ordering::SeqCst);
+        }
+    }
+
+    /// All the stored events have been received: commit the newest one
+    fn eose_received(&self) {
+        self.eose.store(true, Ordering::SeqCst);
+        let stored_event_at: u64 = self.stored_event_at.swap(0, Ordering::SeqCst);
+        self.last_event_at
+            .fetch_max(stored_event_at, Ordering::SeqCst);
+    }
+
+    /// Reset the state of the current session, discarding the uncommitted stored events
+    fn new_session(&self) {
+        self.eose.store(false, Ordering::SeqCst);
+        self.stored_event_at.store(0, Ordering::SeqCst);
+    }
+
+    /// Get the filter to use for the re-subscription
+    ///
+    /// The `since` is advanced to the newest received event (stored events only if EOSE has been received),
+    /// to fill the gap of a disconnection without refetching everything.
+    fn resubscription_filter(&self) -> Filter {
+        let mut filter: Filter = self.filter.clone();
+
+        let last_event_at: u64 = self.last_event_at.load(Ordering::SeqCst);
+        if last_event_at > 0 {
+            let last_event_at: Timestamp = Timestamp::from_secs(last_event_at);
+            if filter.since.map_or(true, |since| last_event_at > since) {
+                filter.since = Some(last_event_at);
+            }
+        }
+
+        filter
+    }
+}
+
 // Instead of wrap every field in an `Arc<T>`, which increases the number of atomic operations,
 // put all fields that require an `Arc` here.
 #[derive(Debug)]
@@ -174,7 +268,7 @@ impl InnerRelay {
                 document: RwLock::new(RelayInformationDocument::new()),
                 #[cfg(feature = "nip11")]
                 last_document_fetch: AtomicU64::new(0),
-                channels: RelayChannels::new(),
+                channels: RelayChannels::new(opts.queue_size),
                 subscriptions: RwLock::new(HashMap::new()),
                 running: AtomicBool::new(false),
             }),
@@ -192,6 +286,13 @@ impl InnerRelay {
         &self.opts.connection_mode
     }
 
+    #[inline]
+    fn websocket_limits(&self) -> WebSocketLimits {
+        WebSocketLimits {
+            max_message_size: self.opts.max_incoming_message_bytes,
+        }
+    }
+
     /// Is connection task running?
     #[inline]
     pub(super) fn is_running(&self) -> bool {
@@ -223,7 +324,7 @@ impl InnerRelay {
         }
 
         // Send notification
-        self.send_notification(RelayNotification::RelayStatus { status }, false);
+        self.send_notification(RelayNotification::RelayStatus { status }, true);
 
         // If monitor is enabled, notify status change.
         if let Some(monitor) = &self.state.monitor {
@@ -288,7 +389,7 @@ impl InnerRelay {
         };
 
         if allowed {
-            let now: u64 = Timestamp::now().as_u64();
+            let now: u64 = self.state.clock.now().as_u64();
 
             // Check last fetch
             if self.atomic.last_document_fetch.load(Ordering::SeqCst) + 3600 < now {
@@ -335,9 +436,35 @@ impl InnerRelay {
         let mut subscriptions = self.atomic.subscriptions.write().await;
         let data: &mut SubscriptionData = subscriptions.entry(id).or_default();
         data.filter = filter;
+        data.last_event_at.store(0, Ordering::SeqCst);
+        data.new_session();
 
         if update_subscribed_at {
-            data.subscribed_at = Timestamp::now();
+            data.subscribed_at = self.state.clock.now();
+        }
+    }
+
+    /// Track the newest `created_at` of the events received for a subscription
+    async fn subscription_event_received(&self, id: &SubscriptionId, event: &Event) {
+        let subscriptions = self.atomic.subscriptions.read().await;
+        if let Some(data) = subscriptions.get(id) {
+            data.event_received(event);
+        }
+    }
+
+    /// Mark the EOSE of a subscription as received
+    async fn subscription_eose_received(&self, id: &SubscriptionId) {
+        let subscriptions = self.atomic.subscriptions.read().await;
+        if let Some(data) = subscriptions.get(id) {
+            data.eose_received();
+        }
+    }
+
+    /// Start a new session for a subscription (i.e., at re-subscription)
+    async fn subscription_new_session(&self, id: &SubscriptionId) {
+        let subscriptions = self.atomic.subscriptions.read().await;
+        if let Some(data) = subscriptions.get(id) {
+            data.new_session();
         }
     }
 
@@ -406,7 +533,12 @@ impl InnerRelay {
                             message,
                         })
                     }
-                    RelayNotification::RelayStatus { .. } => None,
+                    RelayNotification::RelayStatus { status } => {
+                        Some(RelayPoolNotification::RelayStatus {
+                            relay_url: self.url.clone(),
+                            status,
+                        })
+                    }
                     RelayNotification::Authenticated => None,
                     RelayNotification::AuthenticationFailed => None,
                     RelayNotification::Shutdown => Some(RelayPoolNotification::Shutdown),
@@ -594,13 +726,13 @@ impl InnerRelay {
         // At this stem is NOT required to close the WebSocket connection.
         tokio::select! {
             // Connect
-            res = self.state.transport.connect((&self.url).into(), &self.opts.connection_mode, timeout) => match res {
+            res = self.state.transport.connect_with_limits((&self.url).into(), &self.opts.connection_mode, timeout, self.websocket_limits()) => match res {
                 Ok((ws_tx, ws_rx)) => {
                     // Update status
                     self.set_status(RelayStatus::Connected, true);
 
                     // Increment success stats
-                    self.stats.new_success();
+                    self.stats.new_success(self.state.clock.now());
 
                     Ok((ws_tx, ws_rx))
                 }
@@ -933,6 +1065,8 @@ impl InnerRelay {
                             id = %id,
                             "Received EOSE."
                         );
+
+                        self.subscription_eose_received(id).await;
                     }
                     RelayMessage::Closed {
                         subscription_id,
@@ -1010,6 +1144,25 @@ impl InnerRelay {
                 self.send_notification(RelayNotification::Message { message }, true);
             }
             Ok(None) | Err(Error::MessageHandle(MessageHandleError::EmptyMsg)) => (),
+            Err(e @ Error::RelayMessageTooLarge { .. }) => {
+                // Drop the message without logging its content
+                tracing::warn!(
+                    url = %self.url,
+                    error = %e,
+                    "Dropped oversized relay message."
+                );
+            }
+            Err(Error::MessageHandle(e)) => {
+                // Skip the malformed message, keeping the connection alive
+                self.stats.new_malformed_message();
+
+                tracing::warn!(
+                    url = %self.url,
+                    msg = %msg,
+                    error = %e,
+                    "Received malformed relay message."
+                );
+            }
             Err(e) => tracing::error!(
                 url = %self.url,
                 msg = %msg,
@@ -1054,7 +1207,7 @@ impl InnerRelay {
     async fn handle_event_msg(
         &self,
         subscription_id: SubscriptionId,
-        event: Event,
+        mut event: Event,
     ) -> Result<Option<RelayMessage<'static>>, Error> {
         // Check event size
         if let Some(max_size) = self.opts.limits.events.get_max_size(&event.kind) {
@@ -1078,7 +1231,7 @@ impl InnerRelay {
         }
 
         // Check if the event is expired
-        if event.is_expired() {
+        if event.is_expired_at(&self.state.clock.now()) {
             return Err(Error::EventExpired);
         }
 
@@ -1126,7 +1279,7 @@ impl InnerRelay {
             //
             // This is important since event signature verification is a heavy job!
             if !self.state.verified(&event.id)? {
-                event.verify()?;
+                event = self.verify_event(event).await?;
             }
 
             // Save into the database
@@ -1137,8 +1290,12 @@ impl InnerRelay {
                     RejectedReason::Duplicate => true,
                     RejectedReason::Deleted => false,
                     RejectedReason::Expired => false,
+                    RejectedReason::TooOld => true,
                     RejectedReason::Replaced => false,
                     RejectedReason::InvalidDelete => false,
+                    RejectedReason::KindNotAllowed => true,
+                    RejectedReason::Policy => true,
+                    RejectedReason::FutureDated => true,
                     RejectedReason::Other => true,
                 },
             };
@@ -1158,12 +1315,26 @@ impl InnerRelay {
             );
         }
 
+        self.subscription_event_received(&subscription_id, &event)
+            .await;
+
         Ok(Some(RelayMessage::Event {
             subscription_id: Cow::Owned(subscription_id),
             event: Cow::Owned(event),
         }))
     }
 
+    /// Verify the event, offloading it to the verification pool, if configured
+    async fn verify_event(&self, event: Event) -> Result<Event, Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(pool) = &self.state.verification_pool {
+            return pool.verify(event).await;
+        }
+
+        event.verify()?;
+        Ok(event)
+    }
+
     pub fn disconnect(&self) {
         let status = self.status();
 
@@ -1206,6 +1377,29 @@ impl InnerRelay {
     }
 
     pub fn batch_msg(&self, msgs: Vec<ClientMessage<'_>>) -> Result<(), Error> {
+        self.check_batch_msg(&msgs)?;
+
+        // Send messages
+        self.atomic.channels.send_client_msgs(msgs)
+    }
+
+    /// Send messages, applying the [`RelayQueuePolicy`] if the outgoing queue is full
+    pub async fn batch_msg_with_policy(&self, msgs: Vec<ClientMessage<'_>>) -> Result<(), Error> {
+        self.check_batch_msg(&msgs)?;
+
+        // Send messages
+        match self.opts.queue_policy {
+            RelayQueuePolicy::FailFast => self.atomic.channels.send_client_msgs(msgs),
+            RelayQueuePolicy::Block(timeout) => {
+                self.atomic
+                    .channels
+                    .send_client_msgs_wait(msgs, timeout)
+                    .await
+            }
+        }
+    }
+
+    fn check_batch_msg(&self, msgs: &[ClientMessage<'_>]) -> Result<(), Error> {
         // Perform health checks
         self.health_check()?;
 
@@ -1224,8 +1418,7 @@ impl InnerRelay {
             return Err(Error::ReadDisabled);
         }
 
-        // Send messages
-        self.atomic.channels.send_client_msgs(msgs)
+        Ok(())
     }
 
     fn send_neg_msg(&self, id: &SubscriptionId, message: &str) -> Result<(), Error> {
@@ -1310,9 +1503,18 @@ impl InnerRelay {
 
     pub async fn resubscribe(&self) -> Result<(), Error> {
         // TODO: avoid subscriptions clone
-        let subscriptions = self.subscriptions().await;
+        let subscriptions: Vec<(SubscriptionId, Filter)> = {
+            let subscriptions = self.atomic.subscriptions.read().await;
+            subscriptions
+                .iter()
+                .filter(|(.., data)| !data.filter.is_empty())
+                .map(|(id, data)| (id.clone(), data.resubscription_filter()))
+                .collect()
+        };
+
         for (id, filter) in subscriptions.into_iter() {
-            if !filter.is_empty() && self.should_resubscribe(&id).await {
+            if self.should_resubscribe(&id).await {
+                self.subscription_new_session(&id).await;
                 self.send_msg(ClientMessage::Req {
                     subscription_id: Cow::Owned(id),
                     filter: Cow::Owned(filter),
@@ -1335,11 +1537,22 @@ impl InnerRelay {
     ) {
         let relay = self.clone(); // <-- FULL RELAY CLONE HERE
         task::spawn(async move {
+            let handle = relay.handle_auto_closing(&id, &filter, opts, notifications, &activity);
+
+            // Stop if the activity receiver has been dropped (i.e., the fetch has been cancelled)
+            let res: Option<HandleAutoClosing> = match &activity {
+                Some(sender) => tokio::select! {
+                    res = handle => res,
+                    _ = sender.closed() => Some(HandleAutoClosing {
+                        to_close: true,
+                        reason: None,
+                    }),
+                },
+                None => handle.await,
+            };
+
             // Check if CLOSE needed
-            let to_close: bool = match relay
-                .handle_auto_closing(&id, &filter, opts, notifications, &activity)
-                .await
-            {
+            let to_close: bool = match res {
                 Some(HandleAutoClosing { to_close, reason }) => {
                     // Send activity
                     if let Some(reason) = reason {
@@ -2106,3 +2319,110 @@ mod benches {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_outgoing_queue_bound() {
+        let channels = Arc::new(RelayChannels::new(2));
+        let msg = || vec![ClientMessage::close(SubscriptionId::new("test"))];
+
+        // Fill the queue
+        channels.send_client_msgs(msg()).unwrap();
+        channels.send_client_msgs(msg()).unwrap();
+        assert_eq!(channels.nostr_queue(), 2);
+
+        // Fail fast
+        assert!(matches!(
+            channels.send_client_msgs(msg()),
+            Err(Error::OutgoingQueueFull)
+        ));
+
+        // Wait, but nobody drains the queue
+        assert!(matches!(
+            channels
+                .send_client_msgs_wait(msg(), Duration::from_millis(100))
+                .await,
+            Err(Error::OutgoingQueueFull)
+        ));
+        assert_eq!(channels.nostr_queue(), 2);
+
+        // Slow transport: drain a message every 50 ms
+        let drain = channels.clone();
+        let handle = tokio::spawn(async move {
+            let mut rx = drain.rx_nostr().await;
+            let mut received: usize = 0;
+            while received < 7 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                if rx.recv().await.is_none() {
+                    break;
+                }
+                received += 1;
+            }
+            received
+        });
+
+        // Wait for free space
+        for _ in 0..5 {
+            channels
+                .send_client_msgs_wait(msg(), Duration::from_secs(2))
+                .await
+                .unwrap();
+            assert!(channels.nostr_queue() <= 2);
+        }
+
+        assert_eq!(handle.await.unwrap(), 7);
+        assert_eq!(channels.nostr_queue(), 0);
+    }
+
+    #[test]
+    fn test_resubscription_filter_drop_before_eose() {
+        let keys = Keys::generate();
+        let since = Timestamp::from_secs(1000);
+        let data = SubscriptionData {
+            filter: Filter::new().kind(Kind::TextNote).since(since),
+            ..Default::default()
+        };
+
+        let note = |created_at: u64| {
+            EventBuilder::text_note("Test")
+                .custom_created_at(Timestamp::from_secs(created_at))
+                .sign_with_keys(&keys)
+                .unwrap()
+        };
+
+        // Stored events received newest-first, then the connection drops before EOSE
+        data.event_received(&note(3000));
+        data.event_received(&note(2900));
+
+        // The older stored events haven't been received yet: `since` must not move
+        assert_eq!(data.resubscription_filter().since, Some(since));
+
+        // Re-subscribe: this time EOSE is received
+        data.new_session();
+        data.event_received(&note(3000));
+        data.event_received(&note(2000));
+        data.eose_received();
+        assert_eq!(
+            data.resubscription_filter().since,
+            Some(Timestamp::from_secs(3000))
+        );
+
+        // Live events advance `since` immediately
+        data.event_received(&note(3100));
+        assert_eq!(
+            data.resubscription_filter().since,
+            Some(Timestamp::from_secs(3100))
+        );
+
+        // A new session dropped before EOSE keeps the committed `since`
+        data.new_session();
+        data.event_received(&note(3200));
+        assert_eq!(
+            data.resubscription_filter().since,
+            Some(Timestamp::from_secs(3100))
+        );
+    }
+}
@@ -32,8 +32,8 @@ pub use self::flags::{AtomicRelayServiceFlags, FlagCheck, RelayServiceFlags};
 use self::inner::InnerRelay;
 pub use self::limits::RelayLimits;
 pub use self::options::{
-    RelayOptions, ReqExitPolicy, SubscribeAutoCloseOptions, SubscribeOptions, SyncDirection,
-    SyncOptions, SyncProgress,
+    RelayOptions, RelayQueuePolicy, ReqExitPolicy, SubscribeAutoCloseOptions, SubscribeOptions,
+    SyncDirection, SyncOptions, SyncProgress,
 };
 pub use self::stats::RelayConnectionStats;
 pub use self::status::RelayStatus;
@@ -382,7 +382,8 @@ impl Relay {
     ) -> Result<(bool, String), Error> {
         // Send the EVENT message
         self.inner
-            .send_msg(ClientMessage::Event(Cow::Borrowed(event)))?;
+            .batch_msg_with_policy(vec![ClientMessage::Event(Cow::Borrowed(event))])
+            .await?;
 
         // Wait for OK
         self.inner
@@ -685,6 +686,46 @@ impl Relay {
         Ok(count)
     }
 
+    /// Measure the round-trip time of a lightweight `REQ`
+    ///
+    /// Send a `REQ` with a `limit: 0` filter and wait for the `EOSE` (or `CLOSED`) message,
+    /// so no event is transferred.
+    pub async fn probe_latency(&self, timeout: Duration) -> Result<Duration, Error> {
+        // Perform health checks
+        self.inner.health_check()?;
+
+        let id = SubscriptionId::generate();
+        let mut notifications = self.inner.internal_notification_sender.subscribe();
+
+        let start: Instant = Instant::now();
+        self.inner
+            .send_msg(ClientMessage::req(id.clone(), Filter::new().limit(0)))?;
+
+        let res: Option<()> = time::timeout(Some(timeout), async {
+            while let Ok(notification) = notifications.recv().await {
+                if let RelayNotification::Message { message } = notification {
+                    match message {
+                        RelayMessage::EndOfStoredEvents(subscription_id)
+                        | RelayMessage::Closed {
+                            subscription_id, ..
+                        } if subscription_id.as_ref() == &id => break,
+                        _ => {}
+                    }
+                }
+            }
+        })
+        .await;
+
+        let latency: Duration = start.elapsed();
+
+        // Unsubscribe, also if the relay didn't reply
+        self.inner.send_msg(ClientMessage::close(id))?;
+
+        res.ok_or(Error::Timeout)?;
+
+        Ok(latency)
+    }
+
     /// Sync events with relays (negentropy reconciliation)
     pub async fn sync(&self, filter: Filter, opts: &SyncOptions) -> Result<Reconciliation, Error> {
         let items = self
@@ -745,10 +786,55 @@ mod tests {
     use std::sync::Arc;
 
     use async_utility::time;
+    use async_wsocket::futures_util::{sink, stream, StreamExt};
+    use async_wsocket::{ConnectionMode, Message};
+    use nostr::types::{Clock, SystemClock};
+    use nostr::Url;
     use nostr_relay_builder::prelude::*;
 
     use super::{Error, *};
     use crate::policy::{AdmitPolicy, PolicyError};
+    use crate::transport::error::TransportError;
+    use crate::transport::websocket::{BoxSink, BoxStream, WebSocketTransport};
+
+    /// Transport that replays a list of raw text messages and then stays idle
+    ///
+    /// The messages sent by the relay are recorded in `sent`.
+    #[derive(Debug, Default)]
+    struct RawMessagesTransport {
+        messages: Vec<String>,
+        sent: Arc<std::sync::Mutex<Vec<Message>>>,
+    }
+
+    impl WebSocketTransport for RawMessagesTransport {
+        fn support_ping(&self) -> bool {
+            false
+        }
+
+        fn connect<'a>(
+            &'a self,
+            _url: &'a Url,
+            _mode: &'a ConnectionMode,
+            _timeout: Duration,
+        ) -> BoxedFuture<'a, Result<(BoxSink, BoxStream), TransportError>> {
+            Box::pin(async move {
+                let sink: BoxSink = Box::new(Box::pin(sink::unfold(
+                    self.sent.clone(),
+                    |sent, msg: Message| async move {
+                        sent.lock().unwrap().push(msg);
+                        Ok::<_, TransportError>(sent)
+                    },
+                )));
+                let messages = self
+                    .messages
+                    .clone()
+                    .into_iter()
+                    .map(|m| Ok(Message::Text(m)));
+                let stream: BoxStream = Box::new(stream::iter(messages).chain(stream::pending()));
+                Ok((sink, stream))
+            })
+        }
+    }
 
     #[derive(Debug)]
     struct CustomTestPolicy {
@@ -770,6 +856,27 @@ mod tests {
         }
     }
 
+    /// Query policy that records the received filters
+    #[derive(Debug, Clone, Default)]
+    struct RecordQueriesPolicy {
+        filters: Arc<tokio::sync::Mutex<Vec<Filter>>>,
+        received: Arc<tokio::sync::Notify>,
+    }
+
+    impl QueryPolicy for RecordQueriesPolicy {
+        fn admit_query<'a>(
+            &'a self,
+            query: &'a Filter,
+            _addr: &'a std::net::SocketAddr,
+        ) -> BoxedFuture<'a, PolicyResult> {
+            Box::pin(async move {
+                self.filters.lock().await.push(query.clone());
+                self.received.notify_one();
+                PolicyResult::Accept
+            })
+        }
+    }
+
     fn new_relay(url: RelayUrl, opts: RelayOptions) -> Relay {
         Relay::new(url, SharedState::default(), opts)
     }
@@ -818,6 +925,277 @@ mod tests {
         (id, relay, mock)
     }
 
+    #[tokio::test]
+    async fn test_malformed_message() {
+        let event = EventBuilder::text_note("Test")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let valid = RelayMessage::event(SubscriptionId::new("test"), event.clone()).as_json();
+
+        let transport = RawMessagesTransport {
+            messages: vec![String::from(r#"["EVENT","test",{"id":"#), valid],
+            ..Default::default()
+        };
+        let state = SharedState::new(
+            MemoryDatabase::new().into_nostr_database(),
+            Arc::new(transport),
+            None,
+            None,
+            true,
+            None,
+            Arc::new(SystemClock),
+        );
+
+        let url = RelayUrl::parse("ws://127.0.0.1:1").unwrap();
+        let relay: Relay = Relay::new(url, state, RelayOptions::default());
+        let mut notifications = relay.notifications();
+        relay.connect();
+
+        // The valid event still arrives
+        let received = time::timeout(Some(Duration::from_secs(5)), async {
+            while let Ok(notification) = notifications.recv().await {
+                if let RelayNotification::Event { event, .. } = notification {
+                    return Some(*event);
+                }
+            }
+            None
+        })
+        .await
+        .flatten();
+        assert_eq!(received, Some(event));
+
+        // The connection is still alive
+        assert_eq!(relay.status(), RelayStatus::Connected);
+        assert_eq!(relay.stats().malformed_messages(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_incoming_message_bytes() {
+        // Protected by default
+        assert_eq!(
+            RelayOptions::default().max_incoming_message_bytes,
+            Some(5 * 1024 * 1024)
+        );
+
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        // Publish a small and a big event
+        let keys = Keys::generate();
+        let small = EventBuilder::text_note("Test")
+            .sign_with_keys(&keys)
+            .unwrap();
+        let big = EventBuilder::text_note("a".repeat(2048))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let publisher: Relay = new_relay(url.clone(), RelayOptions::default());
+        publisher.try_connect(Duration::from_secs(3)).await.unwrap();
+        publisher.send_event(&small).await.unwrap();
+        publisher.send_event(&big).await.unwrap();
+
+        let opts = RelayOptions::default()
+            .reconnect(false)
+            .max_incoming_message_bytes(Some(1024));
+        let relay: Relay = new_relay(url, opts);
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        // Messages under the limit are received
+        let events = relay
+            .fetch_events(
+                Filter::new().id(small.id),
+                Duration::from_secs(5),
+                ReqExitPolicy::ExitOnEOSE,
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.to_vec(), vec![small]);
+
+        // The oversized message closes the connection
+        let res = relay
+            .fetch_events(
+                Filter::new().id(big.id),
+                Duration::from_secs(2),
+                ReqExitPolicy::ExitOnEOSE,
+            )
+            .await;
+        assert!(res.map(|events| events.is_empty()).unwrap_or(true));
+        assert_ne!(relay.status(), RelayStatus::Connected);
+    }
+
+    /// Clock stuck at a fixed timestamp
+    #[derive(Debug)]
+    struct FixedClock(Timestamp);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> Timestamp {
+            self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_use_clock() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        let now = Timestamp::from_secs(1_000_000);
+        let mut state = SharedState::default();
+        state.clock = Arc::new(FixedClock(now));
+
+        let relay: Relay = Relay::new(url, state, RelayOptions::default());
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        assert_eq!(relay.stats().connected_at(), now);
+        assert_eq!(relay.stats().first_connection_timestamp(), now);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_events_older_than_database_max_age() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        // Publish an event older than the max age
+        let event = EventBuilder::text_note("Old")
+            .custom_created_at(Timestamp::now() - Duration::from_secs(7200))
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let publisher: Relay = new_relay(url.clone(), RelayOptions::default());
+        publisher.try_connect(Duration::from_secs(3)).await.unwrap();
+        publisher.send_event(&event).await.unwrap();
+
+        // The database doesn't store the events older than 1 hour
+        let database = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            max_age: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        });
+        let mut state = SharedState::default();
+        state.database = database.into_nostr_database();
+
+        let relay: Relay = Relay::new(url, state, RelayOptions::default());
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        // The event isn't stored, but is still returned
+        let events = relay
+            .fetch_events(
+                Filter::new().id(event.id),
+                Duration::from_secs(5),
+                ReqExitPolicy::ExitOnEOSE,
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.to_vec(), vec![event.clone()]);
+        assert!(relay
+            .inner
+            .state
+            .database()
+            .event_by_id(&event.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    /// Database policy that rejects all the events
+    #[derive(Debug)]
+    struct RejectAllPolicy;
+
+    impl DatabaseAdmitPolicy for RejectAllPolicy {
+        fn admit_event<'a>(
+            &'a self,
+            _event: &'a Event,
+        ) -> BoxedFuture<'a, Result<bool, DatabaseError>> {
+            Box::pin(async move { Ok(false) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_events_rejected_by_database_policy() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        let event = EventBuilder::text_note("Test")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let publisher: Relay = new_relay(url.clone(), RelayOptions::default());
+        publisher.try_connect(Duration::from_secs(3)).await.unwrap();
+        publisher.send_event(&event).await.unwrap();
+
+        let database = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            admit_policy: Some(Arc::new(RejectAllPolicy)),
+            ..Default::default()
+        });
+        let mut state = SharedState::default();
+        state.database = database.into_nostr_database();
+
+        let relay: Relay = Relay::new(url, state, RelayOptions::default());
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        // The event isn't stored, but is still returned
+        let events = relay
+            .fetch_events(
+                Filter::new().id(event.id),
+                Duration::from_secs(5),
+                ReqExitPolicy::ExitOnEOSE,
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.to_vec(), vec![event.clone()]);
+        assert!(relay
+            .inner
+            .state
+            .database()
+            .event_by_id(&event.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_future_dated_events() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        // Publish an event too far in the future
+        let event = EventBuilder::text_note("Future")
+            .custom_created_at(Timestamp::now() + Duration::from_secs(600))
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let publisher: Relay = new_relay(url.clone(), RelayOptions::default());
+        publisher.try_connect(Duration::from_secs(3)).await.unwrap();
+        publisher.send_event(&event).await.unwrap();
+
+        // The database doesn't store the events more than 1 minute in the future
+        let database = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            max_future_drift: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+        let mut state = SharedState::default();
+        state.database = database.into_nostr_database();
+
+        let relay: Relay = Relay::new(url, state, RelayOptions::default());
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        // The event isn't stored, but is still returned
+        let events = relay
+            .fetch_events(
+                Filter::new().id(event.id),
+                Duration::from_secs(5),
+                ReqExitPolicy::ExitOnEOSE,
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.to_vec(), vec![event.clone()]);
+        assert!(relay
+            .inner
+            .state
+            .database()
+            .event_by_id(&event.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_ok_msg() {
         // Mock relay
@@ -1292,6 +1670,52 @@ mod tests {
         assert_eq!(events.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_probe_latency() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        let relay: Relay = new_relay(url, RelayOptions::default());
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        let latency = relay.probe_latency(Duration::from_secs(5)).await.unwrap();
+        assert!(latency < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_probe_latency_timeout_closes_subscription() {
+        // The relay never replies
+        let transport = RawMessagesTransport::default();
+        let sent = transport.sent.clone();
+        let mut state = SharedState::default();
+        state.transport = Arc::new(transport);
+
+        let url = RelayUrl::parse("ws://127.0.0.1:1").unwrap();
+        let relay: Relay = Relay::new(url, state, RelayOptions::default());
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        let err = relay
+            .probe_latency(Duration::from_millis(500))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout));
+
+        // The probe subscription is closed anyway
+        let is_close = |msg: &Message| match msg {
+            Message::Text(json) => {
+                matches!(ClientMessage::from_json(json), Ok(ClientMessage::Close(..)))
+            }
+            _ => false,
+        };
+        let closed = time::timeout(Some(Duration::from_secs(5)), async {
+            while !sent.lock().unwrap().iter().any(is_close) {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await;
+        assert!(closed.is_some());
+    }
+
     #[tokio::test]
     async fn test_fetch_events_wait_for_events() {
         let (relay, _mock) = setup_event_fetching_relay(5).await;
@@ -1469,6 +1893,101 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_resubscribe_since_last_event() {
+        let policy = RecordQueriesPolicy::default();
+        let builder = RelayBuilder::default().query_policy(policy.clone());
+        let mock = LocalRelay::run(builder).await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+        let port: u16 = Url::parse(&mock.url()).unwrap().port().unwrap();
+
+        let opts = RelayOptions::default()
+            .retry_interval(Duration::from_secs(1))
+            .adjust_retry_interval(false);
+        let relay: Relay = new_relay(url, opts);
+        relay.try_connect(Duration::from_secs(3)).await.unwrap();
+
+        // Publish some events
+        let keys = Keys::generate();
+        let now = Timestamp::now();
+        let mut newest = Timestamp::zero();
+        for i in (1..=3).rev() {
+            let event = EventBuilder::text_note(format!("Text note #{i}"))
+                .custom_created_at(now - Duration::from_secs(i * 100))
+                .sign_with_keys(&keys)
+                .unwrap();
+            newest = event.created_at;
+            relay.send_event(&event).await.unwrap();
+        }
+
+        // Subscribe and wait for the events
+        let mut notifications = relay.notifications();
+        let since = now - Duration::from_secs(3600);
+        let filter = Filter::new().kind(Kind::TextNote).since(since);
+        let id = relay
+            .subscribe(filter, SubscribeOptions::default())
+            .await
+            .unwrap();
+        let eose = time::timeout(Some(Duration::from_secs(5)), async {
+            while let Ok(notification) = notifications.recv().await {
+                if let RelayNotification::Message {
+                    message: RelayMessage::EndOfStoredEvents(sub_id),
+                } = notification
+                {
+                    if sub_id.as_ref() == &id {
+                        break;
+                    }
+                }
+            }
+        })
+        .await;
+        assert!(eose.is_some());
+
+        // The first REQ has the original `since`
+        assert_eq!(policy.filters.lock().await[0].since, Some(since));
+
+        // Disconnect the relay and wait for the client to notice it
+        mock.shutdown();
+        let disconnected = time::timeout(Some(Duration::from_secs(5)), async {
+            while let Ok(notification) = notifications.recv().await {
+                if let RelayNotification::RelayStatus {
+                    status: RelayStatus::Disconnected,
+                } = notification
+                {
+                    break;
+                }
+            }
+        })
+        .await;
+        assert!(disconnected.is_some());
+
+        // Restart the relay on the same port, as soon as it's released
+        let policy = RecordQueriesPolicy::default();
+        let _mock = time::timeout(Some(Duration::from_secs(5)), async {
+            loop {
+                let builder = RelayBuilder::default()
+                    .port(port)
+                    .query_policy(policy.clone());
+                match LocalRelay::run(builder).await {
+                    Ok(mock) => return mock,
+                    Err(..) => tokio::task::yield_now().await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        // Wait for the reconnection and the re-subscription
+        time::timeout(Some(Duration::from_secs(5)), policy.received.notified())
+            .await
+            .unwrap();
+        let filter: Filter = policy.filters.lock().await[0].clone();
+
+        // The `since` is advanced to the newest received event
+        assert_eq!(filter.since, Some(newest));
+        assert_eq!(filter.kinds, Some([Kind::TextNote].into_iter().collect()));
+    }
+
     #[tokio::test]
     async fn test_unsubscribe() {
         let (id, relay, _mock) = setup_subscription_relay().await;
@@ -9,7 +9,10 @@ use std::time::Duration;
 use async_wsocket::ConnectionMode;
 use tokio::sync::watch::{self, Receiver, Sender};
 
-use super::constants::{DEFAULT_NOTIFICATION_CHANNEL_SIZE, DEFAULT_RETRY_INTERVAL};
+use super::constants::{
+    DEFAULT_NOTIFICATION_CHANNEL_SIZE, DEFAULT_OUTGOING_QUEUE_SIZE, DEFAULT_RETRY_INTERVAL,
+    MAX_MESSAGE_SIZE,
+};
 use super::flags::RelayServiceFlags;
 use crate::RelayLimits;
 
@@ -22,8 +25,11 @@ pub struct RelayOptions {
     pub(super) retry_interval: Duration,
     pub(super) adjust_retry_interval: bool,
     pub(super) limits: RelayLimits,
+    pub(super) max_incoming_message_bytes: Option<usize>,
     pub(super) max_avg_latency: Option<Duration>,
     pub(super) notification_channel_size: usize,
+    pub(super) queue_size: usize,
+    pub(super) queue_policy: RelayQueuePolicy,
 }
 
 impl Default for RelayOptions {
@@ -35,8 +41,11 @@ impl Default for RelayOptions {
             retry_interval: DEFAULT_RETRY_INTERVAL,
             adjust_retry_interval: true,
             limits: RelayLimits::default(),
+            max_incoming_message_bytes: Some(MAX_MESSAGE_SIZE as usize),
             max_avg_latency: None,
             notification_channel_size: DEFAULT_NOTIFICATION_CHANNEL_SIZE,
+            queue_size: DEFAULT_OUTGOING_QUEUE_SIZE,
+            queue_policy: RelayQueuePolicy::default(),
         }
     }
 }
@@ -115,6 +124,16 @@ impl RelayOptions {
         self
     }
 
+    /// Set the max size of incoming websocket messages, in bytes (default: 5 MB)
+    ///
+    /// When a relay sends an oversized message, the connection is closed.
+    /// The limit is applied to all the connection modes. Use `None` to disable it.
+    #[inline]
+    pub fn max_incoming_message_bytes(mut self, max: Option<usize>) -> Self {
+        self.max_incoming_message_bytes = max;
+        self
+    }
+
     /// Set max latency (default: None)
     ///
     /// Relay with an avg. latency greater that this value will be skipped.
@@ -130,6 +149,37 @@ impl RelayOptions {
         self.notification_channel_size = size;
         self
     }
+
+    /// Max number of outgoing message batches queued for the relay (default: [`DEFAULT_OUTGOING_QUEUE_SIZE`])
+    ///
+    /// Protects the memory when the relay is slow to accept the messages.
+    #[inline]
+    pub fn queue_size(mut self, size: usize) -> Self {
+        self.queue_size = size.max(1);
+        self
+    }
+
+    /// Policy applied when the outgoing message queue is full (default: [`RelayQueuePolicy::FailFast`])
+    #[inline]
+    pub fn queue_policy(mut self, policy: RelayQueuePolicy) -> Self {
+        self.queue_policy = policy;
+        self
+    }
+}
+
+/// Outgoing message queue policy
+///
+/// Applied when the outgoing message queue of the relay is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RelayQueuePolicy {
+    /// Immediately fail with [`Error::OutgoingQueueFull`](super::Error::OutgoingQueueFull)
+    #[default]
+    FailFast,
+    /// Wait for free space in the queue, up to the [`Duration`], before failing
+    ///
+    /// Only the async send methods (i.e., [`Relay::send_event`](super::Relay::send_event)) can wait:
+    /// [`Relay::send_msg`](super::Relay::send_msg) and [`Relay::batch_msg`](super::Relay::batch_msg) always fail fast.
+    Block(Duration),
 }
 
 /// Auto-closing subscribe options
@@ -330,6 +380,22 @@ mod tests {
         assert_eq!(opt.initial_timeout, Duration::from_secs(5));
     }
 
+    #[test]
+    fn test_queue_options() {
+        let opts = RelayOptions::default();
+        assert_eq!(opts.queue_size, DEFAULT_OUTGOING_QUEUE_SIZE);
+        assert_eq!(opts.queue_policy, RelayQueuePolicy::FailFast);
+
+        let opts = RelayOptions::default()
+            .queue_size(0)
+            .queue_policy(RelayQueuePolicy::Block(Duration::from_secs(1)));
+        assert_eq!(opts.queue_size, 1);
+        assert_eq!(
+            opts.queue_policy,
+            RelayQueuePolicy::Block(Duration::from_secs(1))
+        );
+    }
+
     #[test]
     fn test_close() {
         let opts = SubscribeOptions::default();
@@ -29,6 +29,7 @@ struct InnerRelayConnectionStats {
     success: AtomicUsize,
     bytes_sent: AtomicUsize,
     bytes_received: AtomicUsize,
+    malformed_messages: AtomicUsize,
     // TODO: keep track of msg/event sending attempts and success?
     connected_at: AtomicU64,
     first_connection_at: AtomicU64,
@@ -77,6 +78,14 @@ impl RelayConnectionStats {
         self.inner.bytes_received.load(Ordering::SeqCst)
     }
 
+    /// The number of malformed messages received from the relay
+    ///
+    /// Malformed messages are skipped: can be used to track the reputation of the relay.
+    #[inline]
+    pub fn malformed_messages(&self) -> usize {
+        self.inner.malformed_messages.load(Ordering::SeqCst)
+    }
+
     /// Get UNIX timestamp of the last connection
     #[inline]
     pub fn connected_at(&self) -> Timestamp {
@@ -109,10 +118,10 @@ impl RelayConnectionStats {
         self.inner.attempts.fetch_add(1, Ordering::SeqCst);
     }
 
-    pub(super) fn new_success(&self) {
+    pub(super) fn new_success(&self, now: Timestamp) {
         self.inner.success.fetch_add(1, Ordering::SeqCst);
 
-        let now: u64 = Timestamp::now().as_u64();
+        let now: u64 = now.as_u64();
 
         self.inner.connected_at.store(now, Ordering::SeqCst);
 
@@ -135,6 +144,11 @@ impl RelayConnectionStats {
         }
     }
 
+    #[inline]
+    pub(super) fn new_malformed_message(&self) {
+        self.inner.malformed_messages.fetch_add(1, Ordering::SeqCst);
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub(super) fn save_latency(&self, latency: Duration) {
         let ms: u128 = latency.as_millis();
@@ -156,12 +170,24 @@ mod tests {
     fn test_attempt_success() {
         let stats = RelayConnectionStats::default();
         stats.new_attempt();
-        stats.new_success();
+        stats.new_success(Timestamp::from_secs(100));
         assert_eq!(stats.attempts(), 1);
         assert_eq!(stats.success(), 1);
         assert_eq!(stats.success_rate(), 1.0);
-        assert!(stats.connected_at().as_u64() > 0);
-        assert!(stats.first_connection_timestamp().as_u64() > 0);
+        assert_eq!(stats.connected_at(), Timestamp::from_secs(100));
+        assert_eq!(
+            stats.first_connection_timestamp(),
+            Timestamp::from_secs(100)
+        );
+
+        // The first connection timestamp is kept
+        stats.new_attempt();
+        stats.new_success(Timestamp::from_secs(200));
+        assert_eq!(stats.connected_at(), Timestamp::from_secs(200));
+        assert_eq!(
+            stats.first_connection_timestamp(),
+            Timestamp::from_secs(100)
+        );
     }
 
     #[test]
@@ -11,6 +11,7 @@ use std::sync::{Arc, Mutex};
 
 use lru::LruCache;
 use nostr::prelude::IntoNostrSigner;
+use nostr::types::{Clock, SystemClock};
 use nostr::{EventId, NostrSigner};
 use nostr_database::{IntoNostrDatabase, MemoryDatabase, NostrDatabase};
 use tokio::sync::RwLock;
@@ -18,6 +19,8 @@ use tokio::sync::RwLock;
 use crate::monitor::Monitor;
 use crate::policy::AdmitPolicy;
 use crate::transport::websocket::{DefaultWebsocketTransport, WebSocketTransport};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::verification::VerificationPool;
 
 // LruCache pre-allocate, so keep this at a reasonable value.
 // A good value may be <= 128k, considering that stored values are the 64-bit hashes of the event IDs.
@@ -47,8 +50,11 @@ pub struct SharedState {
     signer: Arc<RwLock<Option<Arc<dyn NostrSigner>>>>,
     nip42_auto_authentication: Arc<AtomicBool>,
     verification_cache: Arc<Mutex<LruCache<u64, ()>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) verification_pool: Option<VerificationPool>,
     pub(crate) admit_policy: Option<Arc<dyn AdmitPolicy>>,
     pub(crate) monitor: Option<Monitor>,
+    pub(crate) clock: Arc<dyn Clock>,
 }
 
 impl Default for SharedState {
@@ -60,6 +66,7 @@ impl Default for SharedState {
             None,
             true,
             None,
+            Arc::new(SystemClock),
         )
     }
 }
@@ -72,6 +79,7 @@ impl SharedState {
         admit_policy: Option<Arc<dyn AdmitPolicy>>,
         nip42_auto_authentication: bool,
         monitor: Option<Monitor>,
+        clock: Arc<dyn Clock>,
     ) -> Self {
         let max_verification_cache_size: NonZeroUsize =
             NonZeroUsize::new(MAX_VERIFICATION_CACHE_SIZE)
@@ -83,11 +91,21 @@ impl SharedState {
             signer: Arc::new(RwLock::new(signer)),
             nip42_auto_authentication: Arc::new(AtomicBool::new(nip42_auto_authentication)),
             verification_cache: Arc::new(Mutex::new(LruCache::new(max_verification_cache_size))),
+            #[cfg(not(target_arch = "wasm32"))]
+            verification_pool: None,
             admit_policy,
             monitor,
+            clock,
         }
     }
 
+    /// Verify the received events using a bounded pool of blocking workers
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn with_verification_workers(mut self, workers: usize) -> Self {
+        self.verification_pool = Some(VerificationPool::new(workers));
+        self
+    }
+
     /// Check if auto authentication to relays is enabled
     #[inline]
     pub fn is_auto_authentication_enabled(&self) -> bool {
@@ -8,7 +8,7 @@ use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
-use async_wsocket::futures_util::{Sink, SinkExt, Stream, StreamExt, TryStreamExt};
+use async_wsocket::futures_util::{future, Sink, SinkExt, Stream, StreamExt, TryStreamExt};
 use async_wsocket::{ConnectionMode, Message, WebSocket};
 use nostr::util::BoxedFuture;
 use nostr::Url;
@@ -57,6 +57,21 @@ where
     }
 }
 
+/// WebSocket connection limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WebSocketLimits {
+    /// Max size of an incoming message, in bytes (default: None)
+    pub max_message_size: Option<usize>,
+}
+
+impl WebSocketLimits {
+    /// Check if no limit is set
+    #[inline]
+    pub fn is_unbounded(&self) -> bool {
+        self.max_message_size.is_none()
+    }
+}
+
 /// WebSocket transport
 pub trait WebSocketTransport: fmt::Debug + Send + Sync {
     /// Support ping/pong
@@ -69,6 +84,32 @@ pub trait WebSocketTransport: fmt::Debug + Send + Sync {
         mode: &'a ConnectionMode,
         timeout: Duration,
     ) -> BoxedFuture<'a, Result<(BoxSink, BoxStream), TransportError>>;
+
+    /// Connect, capping the size of the incoming messages
+    ///
+    /// The default implementation calls [`WebSocketTransport::connect`]
+    /// and makes the stream fail on the first message exceeding the limits, closing the connection.
+    fn connect_with_limits<'a>(
+        &'a self,
+        url: &'a Url,
+        mode: &'a ConnectionMode,
+        timeout: Duration,
+        limits: WebSocketLimits,
+    ) -> BoxedFuture<'a, Result<(BoxSink, BoxStream), TransportError>> {
+        Box::pin(async move {
+            let (sink, stream) = self.connect(url, mode, timeout).await?;
+
+            match limits.max_message_size {
+                Some(max) => {
+                    let stream: BoxStream = Box::new(
+                        stream.and_then(move |msg| future::ready(check_message_size(msg, max))),
+                    ) as BoxStream;
+                    Ok((sink, stream))
+                }
+                None => Ok((sink, stream)),
+            }
+        })
+    }
 }
 
 /// Default websocket transport
@@ -100,3 +141,49 @@ impl WebSocketTransport for DefaultWebsocketTransport {
         })
     }
 }
+
+fn check_message_size(msg: Message, max: usize) -> Result<Message, TransportError> {
+    let size: usize = match &msg {
+        Message::Text(text) => text.len(),
+        Message::Binary(data) => data.len(),
+        #[cfg(not(target_arch = "wasm32"))]
+        _ => 0,
+    };
+
+    if size > max {
+        return Err(TransportError::backend(MessageTooLarge { size, max }));
+    }
+
+    Ok(msg)
+}
+
+#[derive(Debug)]
+struct MessageTooLarge {
+    size: usize,
+    max: usize,
+}
+
+impl std::error::Error for MessageTooLarge {}
+
+impl fmt::Display for MessageTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message too large: size={}, max={}", self.size, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_message_size() {
+        let text: String = "a".repeat(1024);
+        assert!(check_message_size(Message::Text(text.clone()), 1024).is_ok());
+        assert!(check_message_size(Message::Text(text), 1023).is_err());
+
+        let msg = Message::Binary(vec![0; 2048]);
+        assert!(check_message_size(msg, 1024).is_err());
+
+        assert!(WebSocketLimits::default().is_unbounded());
+    }
+}
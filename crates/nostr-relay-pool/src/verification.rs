@@ -0,0 +1,106 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Signature verification pool
+
+use std::sync::Arc;
+
+use async_utility::task;
+use nostr::Event;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::relay::Error;
+
+/// Bounded pool of blocking workers used to verify events
+///
+/// Keeps the heavy signature verification out of the async executor threads.
+/// When all the workers are busy, callers wait for a free one (backpressure).
+#[derive(Debug, Clone)]
+pub struct VerificationPool {
+    semaphore: Arc<Semaphore>,
+}
+
+impl VerificationPool {
+    /// New pool with a max number of concurrent workers
+    pub fn new(workers: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(workers.max(1))),
+        }
+    }
+
+    /// Verify both the ID and the signature of the event
+    ///
+    /// Return back the event if it's valid.
+    pub async fn verify(&self, event: Event) -> Result<Event, Error> {
+        // Wait for a free worker
+        let permit: OwnedSemaphorePermit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| Error::VerificationWorkerFailed)?;
+
+        task::spawn_blocking(move || {
+            // Keep the permit until the verification is completed
+            let _permit: OwnedSemaphorePermit = permit;
+            event.verify().map(|()| event)
+        })
+        .await
+        .map_err(|_| Error::VerificationWorkerFailed)?
+        .map_err(Error::Event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_wsocket::futures_util::future;
+    use nostr::prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_burst() {
+        let keys = Keys::generate();
+        let other = Keys::generate();
+
+        let mut events: Vec<Event> = Vec::with_capacity(100);
+        for i in 0..100 {
+            let mut event: Event = EventBuilder::text_note(format!("Event #{i}"))
+                .sign_with_keys(&keys)
+                .unwrap();
+
+            // Make every 10th event invalid, using the signature of another event
+            if i % 10 == 0 {
+                event.sig = EventBuilder::text_note("Other")
+                    .sign_with_keys(&other)
+                    .unwrap()
+                    .sig;
+            }
+
+            events.push(event);
+        }
+
+        let pool = VerificationPool::new(4);
+
+        let results = tokio::time::timeout(
+            Duration::from_secs(10),
+            future::join_all(events.into_iter().map(|e| pool.verify(e))),
+        )
+        .await
+        .unwrap();
+
+        let valid: usize = results.iter().filter(|r| r.is_ok()).count();
+        let invalid: usize = results
+            .iter()
+            .filter(|r| matches!(r, Err(Error::Event(..))))
+            .count();
+        assert_eq!(valid, 90);
+        assert_eq!(invalid, 10);
+
+        // All workers released
+        assert_eq!(pool.semaphore.available_permits(), 4);
+    }
+}
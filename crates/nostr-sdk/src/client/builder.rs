@@ -7,6 +7,7 @@
 use std::sync::Arc;
 
 use nostr::signer::{IntoNostrSigner, NostrSigner};
+use nostr::types::{Clock, SystemClock};
 use nostr_database::memory::MemoryDatabase;
 use nostr_database::{IntoNostrDatabase, NostrDatabase};
 use nostr_relay_pool::monitor::Monitor;
@@ -15,6 +16,7 @@ use nostr_relay_pool::transport::websocket::{
     DefaultWebsocketTransport, IntoWebSocketTransport, WebSocketTransport,
 };
 
+use crate::outbox::OutboxStore;
 use crate::{Client, Options};
 
 /// Client builder
@@ -30,6 +32,10 @@ pub struct ClientBuilder {
     pub database: Arc<dyn NostrDatabase>,
     /// Relay monitor
     pub monitor: Option<Monitor>,
+    /// Outbox store
+    pub outbox_store: Option<Arc<dyn OutboxStore>>,
+    /// Clock
+    pub clock: Arc<dyn Clock>,
     /// Client options
     pub opts: Options,
 }
@@ -42,6 +48,8 @@ impl Default for ClientBuilder {
             admit_policy: None,
             database: Arc::new(MemoryDatabase::default()),
             monitor: None,
+            outbox_store: None,
+            clock: Arc::new(SystemClock),
             opts: Options::default(),
         }
     }
@@ -112,6 +120,31 @@ impl ClientBuilder {
         self
     }
 
+    /// Set the store of the outbox pending events
+    ///
+    /// Without a store, the pending events are kept only in memory.
+    /// Check [`Options::outbox`] for more details.
+    #[inline]
+    pub fn outbox_store<T>(mut self, store: T) -> Self
+    where
+        T: OutboxStore + 'static,
+    {
+        self.outbox_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Set a custom [`Clock`], used for the time-dependent checks (i.e. NIP-40 expiration)
+    ///
+    /// By default [`SystemClock`] is used.
+    #[inline]
+    pub fn clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Arc::new(clock);
+        self
+    }
+
     /// Set opts
     #[inline]
     pub fn opts(mut self, opts: Options) -> Self {
@@ -21,23 +21,41 @@ pub enum Error {
     Database(DatabaseError),
     /// Signer error
     Signer(SignerError),
+    /// Event error
+    Event(event::Error),
     /// [`EventBuilder`] error
     EventBuilder(event::builder::Error),
     /// Json error
     Json(serde_json::Error),
+    /// I/O error
+    Io(std::io::Error),
+    /// NIP02 error
+    NIP02(nip02::Error),
     /// Shared state error
     SharedState(SharedStateError),
+    /// NIP57 error
+    #[cfg(feature = "nip57")]
+    NIP57(nip57::Error),
     /// NIP59
     #[cfg(feature = "nip59")]
     NIP59(nip59::Error),
+    /// Reqwest error
+    #[cfg(feature = "nip57")]
+    Reqwest(reqwest::Error),
     /// Event not found
     EventNotFound(EventId),
+    /// Event not persisted into the database
+    EventNotPersisted(EventId),
+    /// Contact list not found
+    ContactListNotFound,
     /// Impossible to zap
     ImpossibleToZap(String),
     /// Broken down filters for gossip are empty
     GossipFiltersEmpty,
     /// Private message (NIP17) relays not found
     PrivateMsgRelaysNotFound,
+    /// Import worker failed
+    ImportWorkerFailed,
 }
 
 impl std::error::Error for Error {}
@@ -49,14 +67,25 @@ impl fmt::Display for Error {
             Self::RelayPool(e) => write!(f, "{e}"),
             Self::Database(e) => write!(f, "{e}"),
             Self::Signer(e) => write!(f, "{e}"),
+            Self::Event(e) => write!(f, "{e}"),
             Self::EventBuilder(e) => write!(f, "{e}"),
             Self::Json(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "{e}"),
+            Self::NIP02(e) => write!(f, "{e}"),
             Self::SharedState(e) => write!(f, "{e}"),
+            #[cfg(feature = "nip57")]
+            Self::NIP57(e) => write!(f, "{e}"),
             #[cfg(feature = "nip59")]
             Self::NIP59(e) => write!(f, "{e}"),
+            #[cfg(feature = "nip57")]
+            Self::Reqwest(e) => write!(f, "{e}"),
             Self::EventNotFound(id) => {
                 write!(f, "event not found: {id}")
             }
+            Self::EventNotPersisted(id) => {
+                write!(f, "event not persisted into the database: {id}")
+            }
+            Self::ContactListNotFound => write!(f, "contact list not found"),
             Self::ImpossibleToZap(id) => {
                 write!(f, "impossible to send zap: {id}")
             }
@@ -64,6 +93,7 @@ impl fmt::Display for Error {
                 write!(f, "gossip broken down filters are empty")
             }
             Self::PrivateMsgRelaysNotFound => write!(f, "Private message relays not found. The user is not ready to receive private messages."),
+            Self::ImportWorkerFailed => write!(f, "import worker failed"),
         }
     }
 }
@@ -86,6 +116,12 @@ impl From<DatabaseError> for Error {
     }
 }
 
+impl From<event::Error> for Error {
+    fn from(e: event::Error) -> Self {
+        Self::Event(e)
+    }
+}
+
 impl From<SignerError> for Error {
     fn from(e: SignerError) -> Self {
         Self::Signer(e)
@@ -104,12 +140,38 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<nip02::Error> for Error {
+    fn from(e: nip02::Error) -> Self {
+        Self::NIP02(e)
+    }
+}
+
 impl From<SharedStateError> for Error {
     fn from(e: SharedStateError) -> Self {
         Self::SharedState(e)
     }
 }
 
+#[cfg(feature = "nip57")]
+impl From<nip57::Error> for Error {
+    fn from(e: nip57::Error) -> Self {
+        Self::NIP57(e)
+    }
+}
+
+#[cfg(feature = "nip57")]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Reqwest(e)
+    }
+}
+
 #[cfg(feature = "nip59")]
 impl From<nip59::Error> for Error {
     fn from(e: nip59::Error) -> Self {
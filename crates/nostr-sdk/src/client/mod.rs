@@ -4,33 +4,47 @@
 
 //! Client
 
+use std::cmp;
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::BufRead;
 use std::iter;
+use std::pin::pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_utility::futures_util::future;
+use atomic_destructor::StealthClone;
 use nostr::prelude::*;
 use nostr_database::prelude::*;
 use nostr_relay_pool::prelude::*;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, RwLock};
 
 pub mod builder;
 mod error;
 pub mod options;
+mod tag;
 
 pub use self::builder::ClientBuilder;
 pub use self::error::Error;
 pub use self::options::Options;
 #[cfg(not(target_arch = "wasm32"))]
 pub use self::options::{Connection, ConnectionTarget};
+pub use self::tag::ClientTag;
 use crate::gossip::{BrokenDownFilters, Gossip};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::import::{self, ImportOptions, ImportStats};
+use crate::metadata;
+use crate::outbox::Outbox;
 
 /// Nostr client
 #[derive(Debug, Clone)]
 pub struct Client {
     pool: RelayPool,
     gossip: Gossip,
+    outbox: Outbox,
+    client_tag: Arc<RwLock<Option<ClientTag>>>,
     opts: Options,
 }
 
@@ -85,16 +99,31 @@ impl Client {
             admit_policy: builder.admit_policy,
             monitor: builder.monitor,
             opts: builder.opts.pool,
+            clock: builder.clock.clone(),
             __database: builder.database,
             __signer: builder.signer,
         };
 
         // Construct client
-        Self {
+        let client: Self = Self {
             pool: pool_builder.build(),
-            gossip: Gossip::new(),
+            gossip: Gossip::new(builder.clock),
+            outbox: Outbox::new(builder.outbox_store),
+            client_tag: Arc::new(RwLock::new(None)),
             opts: builder.opts,
+        };
+
+        // Spawn outbox retry task
+        if client.opts.outbox {
+            client.outbox.spawn_retry_task(client.pool.stealth_clone());
         }
+
+        // Spawn metadata prefetch task
+        if client.opts.prefetch_metadata {
+            metadata::spawn_prefetch_task(client.pool.stealth_clone());
+        }
+
+        client
     }
 
     /// Update minimum POW difficulty for received events
@@ -145,6 +174,23 @@ impl Client {
         self.pool.state().unset_signer().await;
     }
 
+    /// Set the client tag automatically added to the events signed by the client
+    ///
+    /// The tag is added by [`Client::sign_event_builder`] and, consequently, by all the methods that build events,
+    /// except the gift wraps (NIP59), to avoid leaking metadata.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/89.md>
+    pub async fn set_client_tag(&self, tag: ClientTag) {
+        let mut client_tag = self.client_tag.write().await;
+        *client_tag = Some(tag);
+    }
+
+    /// Unset the client tag
+    pub async fn unset_client_tag(&self) {
+        let mut client_tag = self.client_tag.write().await;
+        *client_tag = None;
+    }
+
     /// Get [`RelayPool`]
     #[inline]
     pub fn pool(&self) -> &RelayPool {
@@ -215,6 +261,99 @@ impl Client {
         Ok(self.pool.relay(url).await?)
     }
 
+    /// Check if a relay supports a NIP
+    ///
+    /// Use the cached relay information document (NIP-11) of the relay.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/11.md>
+    #[cfg(feature = "nip11")]
+    pub async fn relay_supports_nip<U>(&self, url: U, nip: u16) -> Result<bool, Error>
+    where
+        U: TryIntoUrl,
+        pool::Error: From<<U as TryIntoUrl>::Err>,
+    {
+        let relay: Relay = self.relay(url).await?;
+        let document: RelayInformationDocument = relay.document().await;
+        Ok(document.supports_nip(nip))
+    }
+
+    /// Resolve a lightning address (`user@domain`) to its LNURL-pay endpoint
+    ///
+    /// The response contains the min/max sendable amounts and whether nostr zaps are supported.
+    ///
+    /// If a proxy is configured (see [`Options::connection`]), it's used for the request.
+    /// The embedded tor client isn't supported.
+    ///
+    /// <https://github.com/lnurl/luds/blob/luds/16.md>
+    #[cfg(feature = "nip57")]
+    pub async fn resolve_lightning_address(
+        &self,
+        address: &str,
+    ) -> Result<nip57::LnUrlPayResponse, Error> {
+        let url: Url = nip57::lightning_address_to_url(address)?;
+        let client: reqwest::Client = self.http_client()?;
+        fetch_lnurl_pay_response(&client, url).await
+    }
+
+    /// Resolve the LNURL-pay endpoint of a profile
+    ///
+    /// Try the lightning address (LUD16) first and, if missing or failing, fall back to the LNURL (LUD06).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/57.md>
+    #[cfg(feature = "nip57")]
+    pub async fn resolve_lnurl_pay(
+        &self,
+        metadata: &Metadata,
+    ) -> Result<nip57::LnUrlPayResponse, Error> {
+        let mut urls: Vec<Url> = Vec::with_capacity(2);
+
+        if let Some(lud16) = metadata.lud16.as_deref().filter(|l| !l.is_empty()) {
+            match nip57::lightning_address_to_url(lud16) {
+                Ok(url) => urls.push(url),
+                Err(e) => tracing::warn!(lud16 = %lud16, error = %e, "Invalid LUD16."),
+            }
+        }
+
+        if let Some(lud06) = metadata.lud06.as_deref().filter(|l| !l.is_empty()) {
+            match nip57::lnurl_to_url(lud06) {
+                Ok(url) => urls.push(url),
+                Err(e) => tracing::warn!(lud06 = %lud06, error = %e, "Invalid LUD06."),
+            }
+        }
+
+        let client: reqwest::Client = self.http_client()?;
+        let mut last_error: Option<Error> = None;
+
+        for url in urls.into_iter() {
+            match fetch_lnurl_pay_response(&client, url.clone()).await {
+                Ok(res) => return Ok(res),
+                Err(e) => {
+                    tracing::warn!(url = %url, error = %e, "Failed to fetch LNURL-pay endpoint.");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| Error::ImpossibleToZap(String::from("LUD06 or LUD16 not found"))))
+    }
+
+    /// HTTP client, using the configured proxy (if any)
+    #[cfg(feature = "nip57")]
+    fn http_client(&self) -> Result<reqwest::Client, Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut builder = reqwest::Client::builder();
+            if let ConnectionMode::Proxy(proxy) = &self.opts.connection.mode {
+                builder = builder.proxy(reqwest::Proxy::all(format!("socks5h://{proxy}"))?);
+            }
+            Ok(builder.build()?)
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        Ok(reqwest::Client::new())
+    }
+
     async fn compose_relay_opts(&self, _url: &RelayUrl) -> RelayOptions {
         let opts: RelayOptions = RelayOptions::new();
 
@@ -433,6 +572,19 @@ impl Client {
         Ok(self.pool.try_connect_relay(url, timeout).await?)
     }
 
+    /// Measure the round-trip time of a previously added relay
+    ///
+    /// Send a lightweight `REQ` and wait for the `EOSE`.
+    /// For further details, see the documentation of [`Relay::probe_latency`].
+    pub async fn ping_relay<U>(&self, url: U, timeout: Duration) -> Result<Duration, Error>
+    where
+        U: TryIntoUrl,
+        pool::Error: From<<U as TryIntoUrl>::Err>,
+    {
+        let relay: Relay = self.relay(url).await?;
+        Ok(relay.probe_latency(timeout).await?)
+    }
+
     /// Disconnect relay
     #[inline]
     pub async fn disconnect_relay<U>(&self, url: U) -> Result<(), Error>
@@ -705,6 +857,22 @@ impl Client {
         Ok(self.pool.sync_with(urls, filter, opts).await?)
     }
 
+    /// Import events from a JSONL dump (one event per line) into the database
+    ///
+    /// The lines are parsed, verified and saved concurrently, using bounded channels for backpressure.
+    /// Malformed lines and invalid events are skipped and counted in the returned [`ImportStats`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn import_events<R>(
+        &self,
+        reader: R,
+        opts: ImportOptions,
+    ) -> Result<ImportStats, Error>
+    where
+        R: BufRead + Send + 'static,
+    {
+        import::import_events(self.database().clone(), reader, opts).await
+    }
+
     /// Fetch events from relays
     ///
     /// # Overview
@@ -749,6 +917,62 @@ impl Client {
             .await?)
     }
 
+    /// Fetch events from relays, until the `cancel` future completes
+    ///
+    /// Same as [`Client::fetch_events`], but the fetch can be aborted early (i.e., the user navigated away)
+    /// by completing the `cancel` future (i.e., a `oneshot` receiver or a cancellation token).
+    ///
+    /// On cancellation, the subscriptions are closed and the events received so far are returned.
+    pub async fn fetch_events_until<F>(
+        &self,
+        filter: Filter,
+        timeout: Duration,
+        cancel: F,
+    ) -> Result<Events, Error>
+    where
+        F: Future<Output = ()>,
+    {
+        let mut events: Events = Events::new(&filter);
+
+        // Stream events
+        let mut stream: ReceiverStream<Event> = self.stream_events(filter, timeout).await?;
+
+        let collect = async {
+            while let Some(event) = stream.next().await {
+                // To find out more about why the `force_insert` was used, search for EVENTS_FORCE_INSERT ine the code.
+                events.force_insert(event);
+            }
+        };
+
+        // Stop collecting the events on cancellation
+        future::select(pin!(collect), pin!(cancel)).await;
+
+        // Dropping the stream closes the subscriptions
+        drop(stream);
+
+        Ok(events)
+    }
+
+    /// Fetch events using the outbox model
+    ///
+    /// Same as [`Client::fetch_events`] with the `gossip` option enabled (see [`Options::gossip`]),
+    /// but without the need of enabling it for the whole client.
+    ///
+    /// For author-scoped filters, each author's sub-filter is routed to the author's outbox (write) relays (NIP65),
+    /// connecting to them on demand.
+    /// Other filters are sent to the read relays.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/65.md>
+    #[inline]
+    pub async fn fetch_events_outbox(
+        &self,
+        filter: Filter,
+        timeout: Duration,
+    ) -> Result<Events, Error> {
+        self.gossip_fetch_events(filter, timeout, ReqExitPolicy::ExitOnEOSE)
+            .await
+    }
+
     /// Fetch events from specific relays
     ///
     /// # Overview
@@ -833,6 +1057,54 @@ impl Client {
         Ok(stored_events.merge(fetched_events))
     }
 
+    /// Get [`Event`] by [`EventId`], looking up the database first
+    ///
+    /// # Overview
+    ///
+    /// If the [`Event`] is stored in the database, it's returned immediately, without querying the relays.
+    /// Otherwise, the [`Event`] is fetched from the specified relays and saved into the database.
+    ///
+    /// The relays must be already added to the pool.
+    ///
+    /// Returns [`None`] if the [`Event`] has not been found.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use nostr_sdk::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Client::default();
+    /// # let id = EventId::all_zeros();
+    /// let event: Option<Event> = client
+    ///     .get_event_by_id(id, ["wss://relay.damus.io"], Duration::from_secs(10))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_event_by_id<I, U>(
+        &self,
+        id: EventId,
+        urls: I,
+        timeout: Duration,
+    ) -> Result<Option<Event>, Error>
+    where
+        I: IntoIterator<Item = U>,
+        U: TryIntoUrl,
+        pool::Error: From<<U as TryIntoUrl>::Err>,
+    {
+        // Check database
+        if let Some(event) = self.database().event_by_id(&id).await? {
+            return Ok(Some(event));
+        }
+
+        // Fetch from relays (received events are automatically saved into the database)
+        let filter: Filter = Filter::new().id(id).limit(1);
+        let events: Events = self.fetch_events_from(urls, filter, timeout).await?;
+
+        Ok(events.first_owned())
+    }
+
     /// Stream events from relays
     ///
     /// # Overview
@@ -946,8 +1218,37 @@ impl Client {
     /// If `gossip` is enabled (see [`Options::gossip`]):
     /// - the [`Event`] will be sent also to NIP65 relays (automatically discovered);
     /// - the gossip data will be updated, if the [`Event`] is a NIP17/NIP65 relay list.
-    #[inline]
+    ///
+    /// # Outbox
+    ///
+    /// If `outbox` is enabled (see [`Options::outbox`]), the [`Event`] is kept as pending
+    /// until at least one relay acknowledges it.
     pub async fn send_event(&self, event: &Event) -> Result<Output<EventId>, Error> {
+        if !self.opts.outbox {
+            return self.internal_send_event(event).await;
+        }
+
+        // Persist the event and mark it as pending
+        self.database().save_event(event).await?;
+
+        // Make sure the event has been persisted (i.e., the database may not store events)
+        if self.database().event_by_id(&event.id).await?.is_none() {
+            return Err(Error::EventNotPersisted(event.id));
+        }
+
+        self.outbox.add(event.clone()).await?;
+
+        let output: Output<EventId> = self.internal_send_event(event).await?;
+
+        // Acknowledged by at least one relay
+        if !output.success.is_empty() {
+            self.outbox.remove(&event.id).await?;
+        }
+
+        Ok(output)
+    }
+
+    async fn internal_send_event(&self, event: &Event) -> Result<Output<EventId>, Error> {
         // NOT gossip, send event to all relays
         if !self.opts.gossip {
             return Ok(self.pool.send_event(event).await?);
@@ -986,11 +1287,67 @@ impl Client {
         Ok(self.pool.send_event_to(urls, event).await?)
     }
 
+    /// Get the events waiting in the outbox
+    ///
+    /// These are the events not yet acknowledged by any relay.
+    /// They'll be automatically re-sent when a relay connects.
+    ///
+    /// Check [`Options::outbox`] for more details.
+    #[inline]
+    pub async fn pending_outbox(&self) -> Vec<Event> {
+        self.outbox.pending().await
+    }
+
+    /// Get the number of events waiting in the outbox
+    ///
+    /// These are the events not yet acknowledged by any relay.
+    ///
+    /// Always `0` if `outbox` is disabled (see [`Options::outbox`]).
+    #[inline]
+    pub async fn pending_publish_count(&self) -> usize {
+        self.outbox.len().await
+    }
+
+    /// Re-send event to the relays that failed in a previous attempt
+    ///
+    /// Take the [`Output`] of a previous send attempt (i.e. [`Client::send_event`])
+    /// and re-send the [`Event`] **only** to the relays that failed.
+    ///
+    /// The returned [`Output`] merges the previous successes with the results of the new attempt.
+    /// If no relay failed, the previous output is returned without sending anything.
+    pub async fn resend_event(
+        &self,
+        event: &Event,
+        output: &Output<EventId>,
+    ) -> Result<Output<EventId>, Error> {
+        // Nothing to re-send
+        if output.failed.is_empty() {
+            return Ok(output.clone());
+        }
+
+        // Re-send event to the failed relays
+        let mut new: Output<EventId> = self
+            .send_event_to(output.failed.keys().cloned(), event)
+            .await?;
+
+        // Merge with the previous successes
+        new.success.extend(output.success.iter().cloned());
+
+        Ok(new)
+    }
+
     /// Build, sign and return [`Event`]
     ///
     /// This method requires a [`NostrSigner`].
-    pub async fn sign_event_builder(&self, builder: EventBuilder) -> Result<Event, Error> {
+    ///
+    /// If set, the client tag is added to the event (see [`Client::set_client_tag`]).
+    pub async fn sign_event_builder(&self, mut builder: EventBuilder) -> Result<Event, Error> {
         let signer = self.signer().await?;
+
+        if let Some(tag) = self.client_tag.read().await.as_ref() {
+            builder = builder.tag(tag.clone().into());
+        }
+
         Ok(builder.sign(&signer).await?)
     }
 
@@ -1125,6 +1482,171 @@ impl Client {
         Ok(contact_list)
     }
 
+    /// Get the mute list from relays
+    ///
+    /// Fetch the latest mute list (kind `10000`) and merge the public items with the decrypted private ones.
+    ///
+    /// This method requires a [`NostrSigner`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/51.md>
+    pub async fn get_mute_list(&self, timeout: Duration) -> Result<MuteList, Error> {
+        let signer = self.signer().await?;
+        let public_key = signer.get_public_key().await?;
+        let filter: Filter = Filter::new()
+            .author(public_key)
+            .kind(Kind::MuteList)
+            .limit(1);
+        let events: Events = self.fetch_events(filter, timeout).await?;
+
+        let mut list: MuteList = MuteList::default();
+
+        // Get first event (result of `fetch_events` is sorted DESC by timestamp)
+        if let Some(event) = events.first_owned() {
+            let private_items: Vec<Tag> = event.decrypt_list_items(&signer).await?;
+
+            for tag in event.tags.into_iter().chain(private_items) {
+                match tag.to_standardized() {
+                    Some(TagStandard::PublicKey {
+                        public_key,
+                        uppercase: false,
+                        ..
+                    }) => list.public_keys.push(public_key),
+                    Some(TagStandard::Hashtag(hashtag)) => list.hashtags.push(hashtag),
+                    Some(TagStandard::Event {
+                        event_id,
+                        uppercase: false,
+                        ..
+                    }) => list.event_ids.push(event_id),
+                    Some(TagStandard::Word(word)) => list.words.push(word),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Set the contact list
+    ///
+    /// Build a contact list event (kind 3) from the [`Contact`]s (including relay hints and aliases) and publish it.
+    ///
+    /// If `merge` is `true`, the contacts are merged with the latest contact list stored in the database:
+    /// the contacts already in the list are updated, the other ones are appended.
+    /// Otherwise, the contact list is replaced.
+    ///
+    /// This method requires a [`NostrSigner`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
+    pub async fn set_contacts(
+        &self,
+        contacts: Vec<Contact>,
+        merge: bool,
+    ) -> Result<Output<EventId>, Error> {
+        let contacts: Vec<Contact> = if merge {
+            let filter: Filter = self.get_contact_list_filter().await?;
+            let events: Events = self.database().query(filter).await?;
+
+            let mut list: Vec<Contact> = match events.first_owned() {
+                Some(event) => event.contacts()?,
+                None => Vec::new(),
+            };
+
+            for contact in contacts.into_iter() {
+                match list.iter_mut().find(|c| c.public_key == contact.public_key) {
+                    Some(c) => *c = contact,
+                    None => list.push(contact),
+                }
+            }
+
+            list
+        } else {
+            contacts
+        };
+
+        let builder = EventBuilder::contact_list(contacts);
+        self.send_event_builder(builder).await
+    }
+
+    /// Follow a public key
+    ///
+    /// Fetch the latest contact list from the database and relays, add the public key and publish the updated list.
+    /// Only the `p` tags are edited: the content and the other tags of the list are preserved.
+    ///
+    /// If no contact list is found (i.e., the fetch timed out or no relay is available),
+    /// returns [`Error::ContactListNotFound`] instead of publishing a list with only the new public key.
+    /// To create a new contact list, use [`Client::set_contacts`].
+    ///
+    /// This method requires a [`NostrSigner`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
+    pub async fn follow(
+        &self,
+        public_key: PublicKey,
+        timeout: Duration,
+    ) -> Result<Output<EventId>, Error> {
+        let event: Event = self.latest_contact_list(timeout).await?;
+
+        let mut tags: Vec<Tag> = event.tags.clone().to_vec();
+
+        if !event.tags.public_keys().any(|p| p == &public_key) {
+            tags.push(Tag::public_key(public_key));
+        }
+
+        self.send_contact_list_update(&event, tags).await
+    }
+
+    /// Unfollow a public key
+    ///
+    /// Fetch the latest contact list from the database and relays, remove the public key and publish the updated list.
+    /// Only the `p` tags are edited: the content and the other tags of the list are preserved.
+    ///
+    /// If no contact list is found (i.e., the fetch timed out or no relay is available),
+    /// returns [`Error::ContactListNotFound`].
+    ///
+    /// This method requires a [`NostrSigner`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
+    pub async fn unfollow(
+        &self,
+        public_key: PublicKey,
+        timeout: Duration,
+    ) -> Result<Output<EventId>, Error> {
+        let event: Event = self.latest_contact_list(timeout).await?;
+
+        let mut tags: Vec<Tag> = event.tags.clone().to_vec();
+        tags.retain(|tag| {
+            !matches!(
+                tag.as_standardized(),
+                Some(TagStandard::PublicKey { public_key: p, uppercase: false, .. }) if p == &public_key
+            )
+        });
+
+        self.send_contact_list_update(&event, tags).await
+    }
+
+    /// Get the latest contact list, from database and relays
+    async fn latest_contact_list(&self, timeout: Duration) -> Result<Event, Error> {
+        let filter: Filter = self.get_contact_list_filter().await?;
+        let events: Events = self.fetch_combined_events(filter, timeout).await?;
+        events.first_owned().ok_or(Error::ContactListNotFound)
+    }
+
+    /// Publish an updated version of the contact list, keeping its content
+    async fn send_contact_list_update(
+        &self,
+        prev: &Event,
+        tags: Vec<Tag>,
+    ) -> Result<Output<EventId>, Error> {
+        // Make sure that the new list replaces the previous one, also if updated within the same second
+        let created_at: Timestamp =
+            cmp::max(Timestamp::now(), prev.created_at + Duration::from_secs(1));
+
+        let builder = EventBuilder::new(Kind::ContactList, prev.content.clone())
+            .tags(tags)
+            .custom_created_at(created_at);
+        self.send_event_builder(builder).await
+    }
+
     /// Get contact list public keys from relays.
     ///
     /// This method requires a [`NostrSigner`].
@@ -1168,6 +1690,45 @@ impl Client {
         Ok(contacts)
     }
 
+    /// React to an event
+    ///
+    /// If the reaction is empty, the `+` (like/upvote) is used.
+    ///
+    /// This method requires a [`NostrSigner`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
+    #[inline]
+    pub async fn react<S>(&self, target: &Event, reaction: S) -> Result<Output<EventId>, Error>
+    where
+        S: Into<String>,
+    {
+        let builder = EventBuilder::reaction(target, reaction);
+        self.send_event_builder(builder).await
+    }
+
+    /// Get the outbox (write) relays of the authors
+    ///
+    /// The NIP65 relay lists are read from the database and, if outdated, fetched from the discovery and read relays.
+    /// Authors without known outbox relays aren't included in the returned map.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/65.md>
+    pub async fn outbox_relays(
+        &self,
+        authors: &[PublicKey],
+    ) -> Result<HashMap<PublicKey, Vec<RelayUrl>>, Error> {
+        // Check and update outdated public keys
+        self.check_and_update_gossip(authors.iter().copied())
+            .await?;
+
+        Ok(self
+            .gossip
+            .map_public_keys_to_nip65_outbox_relays(authors)
+            .await
+            .into_iter()
+            .map(|(public_key, urls)| (public_key, urls.into_iter().collect()))
+            .collect())
+    }
+
     /// Send a private direct message
     ///
     /// If `gossip` is enabled (see [`Options::gossip`]) the message will be sent to the NIP17 relays (automatically discovered).
@@ -1558,3 +2119,824 @@ impl Client {
         Ok(self.pool.sync_targeted(filters, opts).await?)
     }
 }
+
+/// Fetch a LNURL-pay response
+#[cfg(feature = "nip57")]
+async fn fetch_lnurl_pay_response(
+    client: &reqwest::Client,
+    url: Url,
+) -> Result<nip57::LnUrlPayResponse, Error> {
+    let res: reqwest::Response = client.get(url).send().await?.error_for_status()?;
+    let body = res.bytes().await?;
+    Ok(nip57::LnUrlPayResponse::from_json(body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_utility::time;
+    use nostr_relay_builder::prelude::*;
+
+    use super::*;
+    use crate::outbox::{FileOutboxStore, OutboxStore};
+
+    fn memory_database() -> MemoryDatabase {
+        MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        })
+    }
+
+    /// Reject the first event, accept the next ones
+    #[derive(Debug, Default)]
+    struct RejectOnce {
+        rejected: AtomicBool,
+    }
+
+    impl WritePolicy for RejectOnce {
+        fn admit_event<'a>(
+            &'a self,
+            _event: &'a Event,
+            _addr: &'a SocketAddr,
+        ) -> BoxedFuture<'a, PolicyResult> {
+            Box::pin(async move {
+                if self.rejected.swap(true, Ordering::SeqCst) {
+                    PolicyResult::Accept
+                } else {
+                    PolicyResult::Reject("rejected once".to_string())
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_event_by_id_from_database() {
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Test")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let client = Client::builder().database(memory_database()).build();
+        client.database().save_event(&event).await.unwrap();
+
+        // The relay isn't added to the pool: if queried, an error would be returned.
+        let res = client
+            .get_event_by_id(event.id, ["ws://127.0.0.1:1"], Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(res, Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_get_event_by_id_from_relays() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = RelayUrl::parse(&mock.url()).unwrap();
+
+        // Publish the event with another client
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Test")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let publisher = Client::default();
+        publisher.add_relay(&url).await.unwrap();
+        publisher.connect().await;
+        publisher.send_event(&event).await.unwrap();
+
+        let client = Client::builder().database(memory_database()).build();
+        client.add_relay(&url).await.unwrap();
+        client.connect().await;
+
+        assert!(client
+            .database()
+            .event_by_id(&event.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let res = client
+            .get_event_by_id(event.id, [&url], Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(res, Some(event.clone()));
+
+        // The fetched event must have been saved into the database
+        let stored = client.database().event_by_id(&event.id).await.unwrap();
+        assert_eq!(stored, Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_resend_event() {
+        let stable = MockRelay::run().await.unwrap();
+        let flaky = LocalRelay::run(RelayBuilder::default().write_policy(RejectOnce::default()))
+            .await
+            .unwrap();
+
+        let client = Client::default();
+        client.add_relay(stable.url()).await.unwrap();
+        client.add_relay(flaky.url()).await.unwrap();
+        client.connect().await;
+
+        let stable_url = RelayUrl::parse(&stable.url()).unwrap();
+        let flaky_url = RelayUrl::parse(&flaky.url()).unwrap();
+
+        let event = EventBuilder::text_note("Resend")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+
+        // The flaky relay rejects the first attempt
+        let output = client.send_event(&event).await.unwrap();
+        assert!(output.success.contains(&stable_url));
+        assert!(output.failed.contains_key(&flaky_url));
+
+        // Re-send only to the failed relay
+        let output = client.resend_event(&event, &output).await.unwrap();
+        assert_eq!(output.success, HashSet::from([stable_url, flaky_url]));
+        assert!(output.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_outbox() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = mock.url();
+
+        let client = Client::builder()
+            .database(memory_database())
+            .opts(Options::new().outbox(true))
+            .build();
+        client.add_relay(&url).await.unwrap();
+
+        // The outbox doesn't need a monitor
+        assert!(client.monitor().is_none());
+
+        let event = EventBuilder::text_note("Outbox")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+
+        // The relay is not connected
+        let output = client.send_event(&event).await.unwrap();
+        assert!(output.success.is_empty());
+        assert_eq!(client.pending_outbox().await, vec![event.clone()]);
+
+        // Connect and wait for the outbox to drain
+        client.connect().await;
+
+        for _ in 0..50 {
+            if client.pending_outbox().await.is_empty() {
+                break;
+            }
+            time::sleep(Duration::from_millis(100)).await;
+        }
+
+        assert!(client.pending_outbox().await.is_empty());
+
+        // Check if the relay received the event
+        let other = Client::default();
+        other.add_relay(&url).await.unwrap();
+        other.connect().await;
+
+        let events = other
+            .fetch_events(Filter::new().id(event.id), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(events.first_owned(), Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_outbox_restart() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = mock.url();
+
+        let path = std::env::temp_dir().join(format!(
+            "nostr-sdk-outbox-{}",
+            Keys::generate().public_key().to_hex()
+        ));
+
+        let event = EventBuilder::text_note("Outbox restart")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+
+        // Send the event while the relay is not connected
+        {
+            let client = Client::builder()
+                .database(memory_database())
+                .outbox_store(FileOutboxStore::new(&path))
+                .opts(Options::new().outbox(true))
+                .build();
+            client.add_relay(&url).await.unwrap();
+
+            let output = client.send_event(&event).await.unwrap();
+            assert!(output.success.is_empty());
+            assert_eq!(client.pending_outbox().await, vec![event.clone()]);
+
+            client.shutdown().await;
+        }
+
+        // Restart: the pending event is reloaded and sent on connect
+        let client = Client::builder()
+            .database(memory_database())
+            .outbox_store(FileOutboxStore::new(&path))
+            .opts(Options::new().outbox(true))
+            .build();
+        assert_eq!(client.pending_outbox().await, vec![event.clone()]);
+
+        client.add_relay(&url).await.unwrap();
+        client.connect().await;
+
+        for _ in 0..50 {
+            if client.pending_outbox().await.is_empty() {
+                break;
+            }
+            time::sleep(Duration::from_millis(100)).await;
+        }
+
+        assert!(client.pending_outbox().await.is_empty());
+
+        // The store has been drained too
+        assert!(FileOutboxStore::new(&path).load().await.unwrap().is_empty());
+
+        // Check if the relay received the event
+        let other = Client::default();
+        other.add_relay(&url).await.unwrap();
+        other.connect().await;
+
+        let events = other
+            .fetch_events(Filter::new().id(event.id), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(events.first_owned(), Some(event));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_outbox_without_events_database() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = mock.url();
+
+        // The default memory database doesn't store events
+        let client = Client::builder().opts(Options::new().outbox(true)).build();
+        client.add_relay(&url).await.unwrap();
+        client.connect().await;
+
+        let event = EventBuilder::text_note("Outbox")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+
+        let res = client.send_event(&event).await;
+        assert!(matches!(res, Err(Error::EventNotPersisted(id)) if id == event.id));
+        assert!(client.pending_outbox().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_publish_count() {
+        let mock = MockRelay::run().await.unwrap();
+        let url = mock.url();
+
+        let client = Client::builder()
+            .database(memory_database())
+            .opts(Options::new().outbox(true))
+            .build();
+        client.add_relay(&url).await.unwrap();
+
+        assert_eq!(client.pending_publish_count().await, 0);
+
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Pending")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // The relay is not connected
+        let output = client.send_event(&event).await.unwrap();
+        assert!(output.success.is_empty());
+        assert_eq!(client.pending_publish_count().await, 1);
+
+        // Sending the same event again doesn't increase the count
+        client.send_event(&event).await.unwrap();
+        assert_eq!(client.pending_publish_count().await, 1);
+
+        // Connect and wait for the outbox to drain
+        client.connect().await;
+
+        for _ in 0..50 {
+            if client.pending_publish_count().await == 0 {
+                break;
+            }
+            time::sleep(Duration::from_millis(100)).await;
+        }
+
+        assert_eq!(client.pending_publish_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_outbox_relays() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let client = Client::builder().database(memory_database()).build();
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        let damus = RelayUrl::parse("wss://relay.damus.io").unwrap();
+        let nos = RelayUrl::parse("wss://nos.lol").unwrap();
+        let mom = RelayUrl::parse("wss://nostr.mom").unwrap();
+
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let carol = Keys::generate();
+
+        let alice_list = EventBuilder::relay_list([
+            (damus.clone(), Some(RelayMetadata::Write)),
+            (nos.clone(), None),
+            (mom.clone(), Some(RelayMetadata::Read)),
+        ])
+        .sign_with_keys(&alice)
+        .unwrap();
+        let bob_list = EventBuilder::relay_list([
+            (damus.clone(), Some(RelayMetadata::Read)),
+            (mom.clone(), Some(RelayMetadata::Write)),
+        ])
+        .sign_with_keys(&bob)
+        .unwrap();
+
+        client.database().save_event(&alice_list).await.unwrap();
+        client.database().save_event(&bob_list).await.unwrap();
+
+        let relays = client
+            .outbox_relays(&[alice.public_key(), bob.public_key(), carol.public_key()])
+            .await
+            .unwrap();
+        assert_eq!(relays.len(), 2);
+        assert_eq!(relays.get(&alice.public_key()).unwrap(), &vec![nos, damus]);
+        assert_eq!(relays.get(&bob.public_key()).unwrap(), &vec![mom]);
+        assert!(!relays.contains_key(&carol.public_key()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_events_outbox() {
+        let default_relay = MockRelay::run().await.unwrap();
+        let outbox_relay = MockRelay::run().await.unwrap();
+        let outbox_url = RelayUrl::parse(&outbox_relay.url()).unwrap();
+
+        let author = Keys::generate();
+        let relay_list = EventBuilder::relay_list([(outbox_url.clone(), None)])
+            .sign_with_keys(&author)
+            .unwrap();
+        let note = EventBuilder::text_note("From outbox")
+            .sign_with_keys(&author)
+            .unwrap();
+
+        // Publish the note only to the author's outbox relay
+        let publisher = Client::default();
+        publisher.add_relay(&outbox_url).await.unwrap();
+        publisher.connect().await;
+        publisher.send_event(&note).await.unwrap();
+
+        let client = Client::builder().database(memory_database()).build();
+        client.add_relay(default_relay.url()).await.unwrap();
+        client.connect().await;
+        client.database().save_event(&relay_list).await.unwrap();
+
+        let filter = Filter::new()
+            .author(author.public_key())
+            .kind(Kind::TextNote);
+
+        // Not available on the default relays
+        let events = client
+            .fetch_events(filter.clone(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+
+        // Routed to the outbox relay of the author
+        let events = client
+            .fetch_events_outbox(filter, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(events.first_owned(), Some(note));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_metadata() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let author = Keys::generate();
+        let metadata = EventBuilder::metadata(&Metadata::new().name("Unknown author"))
+            .sign_with_keys(&author)
+            .unwrap();
+        let note = EventBuilder::text_note("Hello")
+            .sign_with_keys(&author)
+            .unwrap();
+
+        let publisher = Client::default();
+        publisher.add_relay(mock.url()).await.unwrap();
+        publisher.connect().await;
+        publisher.send_event(&metadata).await.unwrap();
+
+        let client = Client::builder()
+            .database(memory_database())
+            .opts(Options::new().prefetch_metadata(true))
+            .build();
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        // Subscribe to text notes only
+        client
+            .subscribe(Filter::new().kind(Kind::TextNote), None)
+            .await
+            .unwrap();
+        publisher.send_event(&note).await.unwrap();
+
+        // The metadata of the unknown author is fetched in background
+        let fetched = time::timeout(Some(Duration::from_secs(10)), async {
+            loop {
+                if let Some(metadata) = client
+                    .database()
+                    .metadata(author.public_key())
+                    .await
+                    .unwrap()
+                {
+                    return metadata;
+                }
+                time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await;
+        assert_eq!(fetched, Some(Metadata::new().name("Unknown author")));
+    }
+
+    /// Run a mock LNURL server that replies once with the body
+    #[cfg(feature = "nip57")]
+    fn mock_lnurl_server(body: &'static str) -> SocketAddr {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, ..) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            let res = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(res.as_bytes()).unwrap();
+        });
+        addr
+    }
+
+    #[cfg(feature = "nip57")]
+    const LNURL_PAY_RESPONSE: &str = r#"{"callback":"https://example.com/lnurlp/pay/callback","maxSendable":100000000,"minSendable":1000,"metadata":"[[\"text/plain\",\"Pay\"]]","tag":"payRequest","allowsNostr":true,"nostrPubkey":"be1d89794bf92de5dd64c1e60f6a2c70c140abac9932418fee30c5c637fe9479"}"#;
+
+    #[tokio::test]
+    #[cfg(feature = "nip57")]
+    async fn test_fetch_lnurl_pay_response() {
+        let addr: SocketAddr = mock_lnurl_server(LNURL_PAY_RESPONSE);
+
+        let url = Url::parse(&format!("http://{addr}/.well-known/lnurlp/pay")).unwrap();
+        let res = fetch_lnurl_pay_response(&reqwest::Client::new(), url)
+            .await
+            .unwrap();
+        assert_eq!(res.min_sendable, 1_000);
+        assert_eq!(res.max_sendable, 100_000_000);
+        assert!(res.supports_zaps());
+        assert_eq!(
+            res.nostr_pubkey,
+            Some(
+                PublicKey::from_hex(
+                    "be1d89794bf92de5dd64c1e60f6a2c70c140abac9932418fee30c5c637fe9479"
+                )
+                .unwrap()
+            )
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "nip57")]
+    async fn test_resolve_lnurl_pay_fallback() {
+        let addr: SocketAddr = mock_lnurl_server(LNURL_PAY_RESPONSE);
+        let lnurl_url = Url::parse(&format!("http://{addr}/lnurlp/pay")).unwrap();
+
+        // The lightning address is dead, the LNURL works
+        let metadata = Metadata::new()
+            .lud16("pay@127.0.0.1:1")
+            .lud06(nip57::url_to_lnurl(&lnurl_url).unwrap());
+
+        let client = Client::default();
+        let res = client.resolve_lnurl_pay(&metadata).await.unwrap();
+        assert!(res.supports_zaps());
+
+        // Nothing to resolve
+        assert!(client.resolve_lnurl_pay(&Metadata::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_contacts() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let keys = Keys::generate();
+        let client = Client::builder()
+            .signer(keys.clone())
+            .database(memory_database())
+            .build();
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        let alice = Contact {
+            public_key: Keys::generate().public_key(),
+            relay_url: Some(RelayUrl::parse("wss://relay.damus.io").unwrap()),
+            alias: Some(String::from("alice")),
+        };
+        let bob = Contact::new(Keys::generate().public_key());
+        let carol = Contact::new(Keys::generate().public_key());
+
+        // Replace
+        client
+            .set_contacts(vec![alice.clone(), bob.clone()], false)
+            .await
+            .unwrap();
+
+        let filter = Filter::new()
+            .author(keys.public_key())
+            .kind(Kind::ContactList);
+        let event = client
+            .fetch_events(filter.clone(), Duration::from_secs(5))
+            .await
+            .unwrap()
+            .first_owned()
+            .unwrap();
+        let tags: Vec<Vec<String>> = event.tags.into_iter().map(|t| t.to_vec()).collect();
+        assert_eq!(
+            tags,
+            vec![
+                vec![
+                    String::from("p"),
+                    alice.public_key.to_hex(),
+                    String::from("wss://relay.damus.io"),
+                    String::from("alice"),
+                ],
+                vec![String::from("p"), bob.public_key.to_hex()],
+            ]
+        );
+
+        // Avoid same `created_at` of the previous contact list
+        time::sleep(Duration::from_secs(1)).await;
+
+        // Merge
+        client
+            .set_contacts(vec![carol.clone()], true)
+            .await
+            .unwrap();
+
+        let events = client
+            .fetch_events(filter, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            events.first().unwrap().contacts().unwrap(),
+            vec![alice, bob, carol]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_mute_list() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let keys = Keys::generate();
+        let client = Client::builder()
+            .signer(keys.clone())
+            .database(memory_database())
+            .build();
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        // Empty list
+        let list = client.get_mute_list(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(list, MuteList::default());
+
+        let public_muted = Keys::generate().public_key();
+        let private_muted = Keys::generate().public_key();
+        let event_id = EventId::all_zeros();
+
+        let builder = EventBuilder::list(
+            &keys,
+            Kind::MuteList,
+            vec![Tag::public_key(public_muted), Tag::hashtag("spam")],
+            vec![
+                Tag::public_key(private_muted),
+                Tag::event(event_id),
+                Tag::from_standardized(TagStandard::Word(String::from("scam"))),
+            ],
+        )
+        .await
+        .unwrap();
+        client.send_event_builder(builder).await.unwrap();
+
+        // Use another client, with an empty database, to fetch the list from the relay
+        let client = Client::builder()
+            .signer(keys.clone())
+            .database(memory_database())
+            .build();
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        let list = client.get_mute_list(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(
+            list,
+            MuteList {
+                public_keys: vec![public_muted, private_muted],
+                hashtags: vec![String::from("spam")],
+                event_ids: vec![event_id],
+                words: vec![String::from("scam")],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_follow_unfollow() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let keys = Keys::generate();
+        let client = Client::builder()
+            .signer(keys.clone())
+            .database(memory_database())
+            .build();
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        let alice = Contact {
+            public_key: Keys::generate().public_key(),
+            relay_url: Some(RelayUrl::parse("wss://relay.damus.io").unwrap()),
+            alias: Some(String::from("alice")),
+        };
+        let bob = Contact::new(Keys::generate().public_key());
+        let carol = Keys::generate().public_key();
+
+        // No contact list: nothing is published
+        let res = client.follow(carol, Duration::from_secs(1)).await;
+        assert!(matches!(res, Err(Error::ContactListNotFound)));
+        let res = client.unfollow(carol, Duration::from_secs(1)).await;
+        assert!(matches!(res, Err(Error::ContactListNotFound)));
+
+        // Contact list with content and non-`p` tags
+        let content = r#"{"wss://relay.damus.io":{"read":true,"write":true}}"#;
+        let contacts = EventBuilder::contact_list(vec![alice.clone(), bob.clone()])
+            .sign_with_keys(&keys)
+            .unwrap();
+        let builder = EventBuilder::new(Kind::ContactList, content)
+            .tags(contacts.tags.to_vec())
+            .tag(Tag::hashtag("nostr"));
+        client.send_event_builder(builder).await.unwrap();
+
+        let filter = Filter::new()
+            .author(keys.public_key())
+            .kind(Kind::ContactList);
+
+        // Follow
+        client.follow(carol, Duration::from_secs(5)).await.unwrap();
+
+        let event = client
+            .fetch_events(filter.clone(), Duration::from_secs(5))
+            .await
+            .unwrap()
+            .first_owned()
+            .unwrap();
+        assert_eq!(
+            event.contacts().unwrap(),
+            vec![alice.clone(), bob.clone(), Contact::new(carol)]
+        );
+        assert_eq!(event.content, content);
+        assert_eq!(event.tags.hashtags().collect::<Vec<_>>(), vec!["nostr"]);
+
+        // Unfollow, right after the follow
+        client
+            .unfollow(bob.public_key, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let event = client
+            .fetch_events(filter, Duration::from_secs(5))
+            .await
+            .unwrap()
+            .first_owned()
+            .unwrap();
+        assert_eq!(event.contacts().unwrap(), vec![alice, Contact::new(carol)]);
+        assert_eq!(event.content, content);
+        assert_eq!(event.tags.hashtags().collect::<Vec<_>>(), vec!["nostr"]);
+    }
+
+    #[tokio::test]
+    async fn test_client_tag() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let client = Client::new(Keys::generate());
+        client.add_relay(mock.url()).await.unwrap();
+        client.connect().await;
+
+        client.set_client_tag(ClientTag::new("rust-nostr")).await;
+
+        let output = client
+            .send_event_builder(EventBuilder::text_note("Tagged"))
+            .await
+            .unwrap();
+
+        let event = client
+            .fetch_events(Filter::new().id(output.val), Duration::from_secs(5))
+            .await
+            .unwrap()
+            .first_owned()
+            .unwrap();
+        assert_eq!(
+            event.tags.find_standardized(TagKind::Client),
+            Some(&TagStandard::Client {
+                name: String::from("rust-nostr"),
+                address: None,
+            })
+        );
+
+        // Unset
+        client.unset_client_tag().await;
+
+        let event = client
+            .sign_event_builder(EventBuilder::text_note("Untagged"))
+            .await
+            .unwrap();
+        assert!(event.tags.find(TagKind::Client).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ping_relay() {
+        let mock = MockRelay::run().await.unwrap();
+
+        let client = Client::default();
+        client.add_relay(mock.url()).await.unwrap();
+        client
+            .try_connect_relay(mock.url(), Duration::from_secs(3))
+            .await
+            .unwrap();
+
+        let latency = client
+            .ping_relay(mock.url(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(latency < Duration::from_secs(5));
+
+        // Not added relay
+        assert!(client
+            .ping_relay("ws://127.0.0.1:1", Duration::from_secs(1))
+            .await
+            .is_err());
+    }
+
+    /// Delay the queries, to simulate a slow relay
+    #[derive(Debug)]
+    struct SlowQuery;
+
+    impl QueryPolicy for SlowQuery {
+        fn admit_query<'a>(
+            &'a self,
+            _query: &'a Filter,
+            _addr: &'a SocketAddr,
+        ) -> BoxedFuture<'a, PolicyResult> {
+            Box::pin(async move {
+                time::sleep(Duration::from_secs(30)).await;
+                PolicyResult::Accept
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_events_until() {
+        let fast = MockRelay::run().await.unwrap();
+        let slow = LocalRelay::run(RelayBuilder::default().query_policy(SlowQuery))
+            .await
+            .unwrap();
+
+        let client = Client::default();
+        client.add_relay(fast.url()).await.unwrap();
+        client.add_relay(slow.url()).await.unwrap();
+        client.connect().await;
+
+        let event = EventBuilder::text_note("Partial")
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        client.send_event_to([fast.url()], &event).await.unwrap();
+
+        // The slow relay never sends the EOSE before the cancellation
+        let start = Instant::now();
+        let events = client
+            .fetch_events_until(
+                Filter::new().kind(Kind::TextNote),
+                Duration::from_secs(60),
+                time::sleep(Duration::from_secs(2)),
+            )
+            .await
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        // Events received from the fast relay before the cancellation
+        assert_eq!(events.to_vec(), vec![event]);
+    }
+}
@@ -17,6 +17,8 @@ use nostr_relay_pool::prelude::*;
 pub struct Options {
     pub(super) autoconnect: bool,
     pub(super) gossip: bool,
+    pub(super) outbox: bool,
+    pub(super) prefetch_metadata: bool,
     #[cfg(not(target_arch = "wasm32"))]
     pub(super) connection: Connection,
     pub(super) relay_limits: RelayLimits,
@@ -71,6 +73,33 @@ impl Options {
         self
     }
 
+    /// Enable the outbox (default: false)
+    ///
+    /// The events sent with [`Client::send_event`](crate::Client::send_event) are persisted into the database
+    /// and, if no relay acknowledges them, automatically re-sent when a relay (re)connects.
+    ///
+    /// Requires a database that stores events:
+    /// if the event can't be persisted, [`Client::send_event`](crate::Client::send_event) returns an error without sending it.
+    ///
+    /// The pending events are persisted with the outbox store (see [`ClientBuilder::outbox_store`](crate::ClientBuilder::outbox_store))
+    /// and reloaded when the client is built, so they are re-sent also after a restart.
+    /// Without a store, the pending events are kept only in memory.
+    #[inline]
+    pub fn outbox(mut self, enable: bool) -> Self {
+        self.outbox = enable;
+        self
+    }
+
+    /// Automatically fetch the missing metadata of the authors of the received events (default: false)
+    ///
+    /// The unknown authors are collected and their metadata fetched in rate-limited batches,
+    /// storing the results in the database.
+    #[inline]
+    pub fn prefetch_metadata(mut self, enable: bool) -> Self {
+        self.prefetch_metadata = enable;
+        self
+    }
+
     /// Connection mode and target
     #[inline]
     #[cfg(not(target_arch = "wasm32"))]
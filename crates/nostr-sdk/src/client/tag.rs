@@ -0,0 +1,50 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Client tag
+
+use nostr::prelude::*;
+
+/// Client tag
+///
+/// Identify the client that published the events.
+///
+/// <https://github.com/nostr-protocol/nips/blob/master/89.md>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClientTag {
+    /// Client name
+    pub name: String,
+    /// Address of the client handler event (kind `31990`) and optional relay hint
+    pub address: Option<(Coordinate, Option<RelayUrl>)>,
+}
+
+impl ClientTag {
+    /// New client tag
+    #[inline]
+    pub fn new<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            address: None,
+        }
+    }
+
+    /// Set the address of the client handler event (kind `31990`)
+    #[inline]
+    pub fn address(mut self, coordinate: Coordinate, relay_hint: Option<RelayUrl>) -> Self {
+        self.address = Some((coordinate, relay_hint));
+        self
+    }
+}
+
+impl From<ClientTag> for Tag {
+    fn from(tag: ClientTag) -> Self {
+        Self::from_standardized_without_cell(TagStandard::Client {
+            name: tag.name,
+            address: tag.address,
+        })
+    }
+}
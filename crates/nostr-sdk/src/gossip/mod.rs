@@ -48,12 +48,14 @@ type PublicKeyMap = HashMap<PublicKey, RelayLists>;
 pub struct Gossip {
     /// Keep track of seen public keys and of their NIP65
     public_keys: Arc<RwLock<PublicKeyMap>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl Gossip {
-    pub fn new() -> Self {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
         Self {
             public_keys: Arc::new(RwLock::new(HashMap::new())),
+            clock,
         }
     }
 
@@ -98,7 +100,7 @@ impl Gossip {
                                 .map(|(u, m)| (u.clone(), *m))
                                 .collect(),
                             event_created_at: event.created_at,
-                            last_update: Timestamp::now(),
+                            last_update: self.clock.now(),
                         };
                     }
                 })
@@ -109,7 +111,7 @@ impl Gossip {
                             .map(|(u, m)| (u.clone(), *m))
                             .collect(),
                         event_created_at: event.created_at,
-                        last_update: Timestamp::now(),
+                        last_update: self.clock.now(),
                     },
                     ..Default::default()
                 });
@@ -125,7 +127,7 @@ impl Gossip {
                                 .cloned()
                                 .collect(),
                             event_created_at: event.created_at,
-                            last_update: Timestamp::now(),
+                            last_update: self.clock.now(),
                         };
                     }
                 })
@@ -136,7 +138,7 @@ impl Gossip {
                             .cloned()
                             .collect(),
                         event_created_at: event.created_at,
-                        last_update: Timestamp::now(),
+                        last_update: self.clock.now(),
                     },
                     ..Default::default()
                 });
@@ -149,7 +151,7 @@ impl Gossip {
         I: IntoIterator<Item = PublicKey>,
     {
         let map = self.public_keys.read().await;
-        let now = Timestamp::now();
+        let now = self.clock.now();
 
         let mut outdated: HashSet<PublicKey> = HashSet::new();
 
@@ -188,7 +190,7 @@ impl Gossip {
         I: IntoIterator<Item = PublicKey>,
     {
         let mut map = self.public_keys.write().await;
-        let now = Timestamp::now();
+        let now = self.clock.now();
 
         for public_key in public_keys.into_iter() {
             map.entry(public_key)
@@ -325,6 +327,31 @@ impl Gossip {
         self.get_nip65_relays(&txn, public_keys, Some(RelayMetadata::Write))
     }
 
+    /// Get outbox (write) relays for each public key
+    ///
+    /// Public keys without outbox relays are skipped.
+    pub async fn map_public_keys_to_nip65_outbox_relays<'a, I>(
+        &self,
+        public_keys: I,
+    ) -> HashMap<PublicKey, BTreeSet<RelayUrl>>
+    where
+        I: IntoIterator<Item = &'a PublicKey>,
+    {
+        let txn = self.public_keys.read().await;
+        let mut map: HashMap<PublicKey, BTreeSet<RelayUrl>> = HashMap::new();
+
+        for public_key in public_keys.into_iter() {
+            let urls: HashSet<RelayUrl> =
+                self.get_nip65_relays(&txn, [public_key], Some(RelayMetadata::Write));
+
+            if !urls.is_empty() {
+                map.insert(*public_key, urls.into_iter().collect());
+            }
+        }
+
+        map
+    }
+
     /// Get inbox (read) relays for public keys
     #[inline]
     pub async fn get_nip65_inbox_relays<'a, I>(&self, public_keys: I) -> HashSet<RelayUrl>
@@ -502,7 +529,7 @@ mod tests {
     }
 
     async fn setup_graph() -> Gossip {
-        let graph = Gossip::new();
+        let graph = Gossip::new(Arc::new(SystemClock));
 
         let events = vec![
             build_relay_list_event(SECRET_KEY_A, KEY_A_RELAYS.to_vec()),
@@ -0,0 +1,298 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Events import
+//!
+//! Import events from a JSONL dump (one event per line) into the database.
+//!
+//! The import is a pipeline of three stages connected by bounded channels (backpressure):
+//! the lines are parsed on a blocking task, the events are verified by a [`VerificationPool`]
+//! and the valid ones are saved into the database in batches.
+
+use std::io::{self, BufRead};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::task;
+use nostr::types::time::Instant;
+use nostr::{Event, JsonUtil};
+use nostr_database::{NostrDatabase, NostrEventsDatabase, SaveEventStatus};
+use nostr_relay_pool::relay::Error as RelayError;
+use nostr_relay_pool::VerificationPool;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::client::Error;
+
+const DEFAULT_VERIFICATION_WORKERS: usize = 4;
+const DEFAULT_BATCH_SIZE: usize = 500;
+const DEFAULT_CHANNEL_SIZE: usize = 1024;
+
+/// Import options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportOptions {
+    verification_workers: usize,
+    batch_size: usize,
+    channel_size: usize,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            verification_workers: DEFAULT_VERIFICATION_WORKERS,
+            batch_size: DEFAULT_BATCH_SIZE,
+            channel_size: DEFAULT_CHANNEL_SIZE,
+        }
+    }
+}
+
+impl ImportOptions {
+    /// New default options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Max number of concurrent signature verification workers (default: 4)
+    #[inline]
+    pub fn verification_workers(mut self, workers: usize) -> Self {
+        self.verification_workers = workers.max(1);
+        self
+    }
+
+    /// Number of events saved into the database per batch (default: 500)
+    #[inline]
+    pub fn batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Size of the channels between the pipeline stages (default: 1024)
+    ///
+    /// When a channel is full, the previous stage waits (backpressure).
+    #[inline]
+    pub fn channel_size(mut self, size: usize) -> Self {
+        self.channel_size = size.max(1);
+        self
+    }
+}
+
+/// Import statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    /// Number of successfully parsed events
+    pub parsed: usize,
+    /// Number of lines that can't be parsed as event
+    pub malformed: usize,
+    /// Number of events with an invalid ID or signature
+    pub invalid: usize,
+    /// Number of events saved into the database
+    pub saved: usize,
+    /// Number of events rejected by the database (i.e. duplicated, deleted or replaced)
+    pub rejected: usize,
+    /// Duration of the import
+    pub elapsed: Duration,
+}
+
+impl ImportStats {
+    /// Number of parsed events per second
+    pub fn events_per_second(&self) -> f64 {
+        let secs: f64 = self.elapsed.as_secs_f64();
+
+        if secs > 0.0 {
+            self.parsed as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+pub(crate) async fn import_events<R>(
+    database: Arc<dyn NostrDatabase>,
+    reader: R,
+    opts: ImportOptions,
+) -> Result<ImportStats, Error>
+where
+    R: BufRead + Send + 'static,
+{
+    let start: Instant = Instant::now();
+
+    let (parsed_tx, parsed_rx) = mpsc::channel::<Event>(opts.channel_size);
+    let (verified_tx, mut verified_rx) =
+        mpsc::channel::<Result<Event, RelayError>>(opts.channel_size);
+
+    // Parse the lines
+    let parser = task::spawn_blocking(move || -> io::Result<(usize, usize)> {
+        let mut parsed: usize = 0;
+        let mut malformed: usize = 0;
+
+        for line in reader.lines() {
+            let line: String = line?;
+            let line: &str = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match Event::from_json(line) {
+                Ok(event) => {
+                    parsed += 1;
+
+                    // The receiver has been dropped: the import failed
+                    if parsed_tx.blocking_send(event).is_err() {
+                        break;
+                    }
+                }
+                Err(..) => malformed += 1,
+            }
+        }
+
+        Ok((parsed, malformed))
+    });
+
+    // Verify the events
+    let pool: VerificationPool = VerificationPool::new(opts.verification_workers);
+    let parsed_rx: Arc<Mutex<mpsc::Receiver<Event>>> = Arc::new(Mutex::new(parsed_rx));
+    for _ in 0..opts.verification_workers {
+        let pool: VerificationPool = pool.clone();
+        let parsed_rx = parsed_rx.clone();
+        let verified_tx = verified_tx.clone();
+
+        task::spawn(async move {
+            loop {
+                // Release the lock before the verification, to let the other workers receive
+                let event: Event = match parsed_rx.lock().await.recv().await {
+                    Some(event) => event,
+                    None => break,
+                };
+
+                // The receiver has been dropped: the import failed
+                if verified_tx.send(pool.verify(event).await).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    // Drop the last sender, so the channel is closed when all the workers have completed
+    drop(verified_tx);
+
+    // Save the events in batches
+    let mut stats: ImportStats = ImportStats::default();
+    let mut batch: Vec<Event> = Vec::with_capacity(opts.batch_size);
+
+    // The channel is closed when all the verification workers have completed
+    while let Some(event) = verified_rx.recv().await {
+        match event {
+            Ok(event) => {
+                batch.push(event);
+
+                if batch.len() >= opts.batch_size {
+                    save_batch(&database, &mut batch, &mut stats).await?;
+                }
+            }
+            Err(RelayError::Event(..)) => stats.invalid += 1,
+            Err(..) => return Err(Error::ImportWorkerFailed),
+        }
+    }
+
+    save_batch(&database, &mut batch, &mut stats).await?;
+
+    let (parsed, malformed) = parser
+        .await
+        .map_err(|_| Error::ImportWorkerFailed)?
+        .map_err(Error::Io)?;
+    stats.parsed = parsed;
+    stats.malformed = malformed;
+    stats.elapsed = start.elapsed();
+
+    tracing::debug!(
+        parsed = stats.parsed,
+        saved = stats.saved,
+        events_per_second = stats.events_per_second(),
+        "Import completed."
+    );
+
+    Ok(stats)
+}
+
+async fn save_batch(
+    database: &Arc<dyn NostrDatabase>,
+    batch: &mut Vec<Event>,
+    stats: &mut ImportStats,
+) -> Result<(), Error> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    for status in database.save_events(batch.as_slice()).await?.into_iter() {
+        match status {
+            SaveEventStatus::Success => stats.saved += 1,
+            SaveEventStatus::Rejected(..) => stats.rejected += 1,
+        }
+    }
+
+    batch.clear();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use nostr::prelude::*;
+    use nostr_database::{MemoryDatabase, MemoryDatabaseOptions};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_events() {
+        let keys = Keys::generate();
+        let other = Keys::generate();
+
+        let mut dump: String = String::new();
+        for i in 0..3000 {
+            let mut event: Event = EventBuilder::text_note(format!("Event #{i}"))
+                .sign_with_keys(&keys)
+                .unwrap();
+
+            // Make every 10th event invalid, using the signature of another event
+            if i % 10 == 0 {
+                event.sig = EventBuilder::text_note("Other")
+                    .sign_with_keys(&other)
+                    .unwrap()
+                    .sig;
+            }
+
+            dump.push_str(&event.as_json());
+            dump.push('\n');
+        }
+
+        // Malformed and empty lines
+        dump.push_str("{\"not\":\"an event\"}\n\n");
+
+        let database = MemoryDatabase::with_opts(MemoryDatabaseOptions {
+            events: true,
+            ..Default::default()
+        });
+        let database: Arc<dyn NostrDatabase> = Arc::new(database);
+
+        let opts = ImportOptions::new().batch_size(100).channel_size(64);
+        let stats = import_events(database.clone(), Cursor::new(dump), opts)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.parsed, 3000);
+        assert_eq!(stats.malformed, 1);
+        assert_eq!(stats.invalid, 300);
+        assert_eq!(stats.saved, 2700);
+        assert_eq!(stats.rejected, 0);
+
+        let count: usize = database
+            .count(Filter::new().author(keys.public_key))
+            .await
+            .unwrap();
+        assert_eq!(count, 2700);
+    }
+}
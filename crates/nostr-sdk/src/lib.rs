@@ -36,6 +36,15 @@ pub use nostr_relay_pool::{
 
 pub mod client;
 mod gossip;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod import;
+mod metadata;
+pub mod outbox;
 pub mod prelude;
 
 pub use self::client::{Client, ClientBuilder, Options};
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::import::{ImportOptions, ImportStats};
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::outbox::FileOutboxStore;
+pub use self::outbox::OutboxStore;
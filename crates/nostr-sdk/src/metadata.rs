@@ -0,0 +1,105 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Metadata prefetch
+//!
+//! Automatically fetch the metadata of the authors of the received events, if missing in the database.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use async_utility::{task, time};
+use nostr::types::time::Instant;
+use nostr::{Filter, Kind, PublicKey};
+use nostr_relay_pool::relay::ReqExitPolicy;
+use nostr_relay_pool::{RelayPool, RelayPoolNotification};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Min interval between two metadata fetches
+const BATCH_INTERVAL: Duration = Duration::from_secs(2);
+/// Max number of authors per metadata fetch
+const MAX_BATCH_SIZE: usize = 250;
+/// Max number of already requested authors to keep track of
+const MAX_REQUESTED: usize = 50_000;
+/// Metadata fetch timeout
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Spawn a task that collects the unknown authors of the received events and fetch their metadata in batches
+///
+/// The fetched metadata are stored in the database. The task exits when the pool is shutdown.
+pub(crate) fn spawn_prefetch_task(pool: RelayPool) {
+    let mut notifications = pool.notifications();
+
+    task::spawn(async move {
+        // Authors already requested (or queued)
+        let mut requested: HashSet<PublicKey> = HashSet::new();
+        // Authors waiting for the next batch
+        let mut pending: HashSet<PublicKey> = HashSet::new();
+        let mut last_fetch: Instant = Instant::now();
+
+        loop {
+            match time::timeout(Some(BATCH_INTERVAL), notifications.recv()).await {
+                Some(Ok(RelayPoolNotification::Event { event, .. })) => {
+                    if event.kind != Kind::Metadata && !requested.contains(&event.pubkey) {
+                        // Keep memory usage bounded
+                        if requested.len() >= MAX_REQUESTED {
+                            requested.clear();
+                        }
+
+                        requested.insert(event.pubkey);
+                        pending.insert(event.pubkey);
+                    }
+                }
+                Some(Ok(RelayPoolNotification::Shutdown)) | Some(Err(RecvError::Closed)) => break,
+                Some(Ok(..)) | Some(Err(RecvError::Lagged(..))) | None => {}
+            }
+
+            if pool.is_shutdown() {
+                break;
+            }
+
+            // Rate limit the fetches
+            if !pending.is_empty()
+                && (pending.len() >= MAX_BATCH_SIZE || last_fetch.elapsed() >= BATCH_INTERVAL)
+            {
+                let authors: Vec<PublicKey> = pending.drain().collect();
+                task::spawn(fetch_missing_metadata(pool.clone(), authors));
+                last_fetch = Instant::now();
+            }
+        }
+
+        tracing::debug!("Exited from metadata prefetch task.");
+    });
+}
+
+async fn fetch_missing_metadata(pool: RelayPool, authors: Vec<PublicKey>) {
+    // Skip the authors with metadata already in the database
+    let mut missing: Vec<PublicKey> = Vec::with_capacity(authors.len());
+    for author in authors.into_iter() {
+        let filter: Filter = Filter::new().author(author).kind(Kind::Metadata).limit(1);
+        match pool.database().count(filter).await {
+            Ok(0) => missing.push(author),
+            Ok(..) => {}
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to check metadata in the database.");
+                return;
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        return;
+    }
+
+    tracing::debug!(authors = missing.len(), "Prefetching metadata.");
+
+    // The received events are stored in the database by the pool
+    let filter: Filter = Filter::new().authors(missing).kind(Kind::Metadata);
+    if let Err(e) = pool
+        .fetch_events(filter, FETCH_TIMEOUT, ReqExitPolicy::ExitOnEOSE)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to prefetch metadata.");
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Outbox
+//!
+//! Keep track of the events not yet acknowledged by any relay and retry to send them when a relay (re)connects.
+
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_utility::task;
+use nostr::util::BoxedFuture;
+#[cfg(not(target_arch = "wasm32"))]
+use nostr::JsonUtil;
+use nostr::{Event, EventId, RelayUrl};
+use nostr_database::DatabaseError;
+use nostr_relay_pool::{RelayPool, RelayPoolNotification, RelayStatus};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{OnceCell, RwLock};
+
+/// Outbox store
+///
+/// Persist the pending events of the outbox, so they survive the restarts.
+pub trait OutboxStore: fmt::Debug + Send + Sync {
+    /// Load the pending events
+    fn load(&self) -> BoxedFuture<Result<Vec<Event>, DatabaseError>>;
+
+    /// Replace the pending events with the new ones
+    fn store<'a>(&'a self, events: &'a [Event]) -> BoxedFuture<'a, Result<(), DatabaseError>>;
+}
+
+/// File outbox store
+///
+/// Store the pending events in a file, one JSON event per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileOutboxStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileOutboxStore {
+    /// New file outbox store
+    ///
+    /// The file is created on the first write.
+    #[inline]
+    pub fn new<P>(path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl OutboxStore for FileOutboxStore {
+    fn load(&self) -> BoxedFuture<Result<Vec<Event>, DatabaseError>> {
+        Box::pin(async move {
+            let content: String = match fs::read_to_string(&self.path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(DatabaseError::backend(e)),
+            };
+
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| Event::from_json(line).map_err(DatabaseError::backend))
+                .collect()
+        })
+    }
+
+    fn store<'a>(&'a self, events: &'a [Event]) -> BoxedFuture<'a, Result<(), DatabaseError>> {
+        Box::pin(async move {
+            let mut content: String = String::new();
+            for event in events.iter() {
+                content.push_str(&event.as_json());
+                content.push('\n');
+            }
+
+            // Write to a temporary file and rename it, to not leave a truncated file on crash
+            let tmp: PathBuf = self.path.with_extension("tmp");
+            fs::write(&tmp, content).map_err(DatabaseError::backend)?;
+            fs::rename(&tmp, &self.path).map_err(DatabaseError::backend)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Outbox {
+    /// Pending events
+    ///
+    /// The events are also persisted into the database.
+    pending: Arc<RwLock<Vec<Event>>>,
+    /// Store of the pending events
+    store: Option<Arc<dyn OutboxStore>>,
+    /// Whether the pending events have been loaded from the store
+    loaded: Arc<OnceCell<()>>,
+}
+
+impl Outbox {
+    pub fn new(store: Option<Arc<dyn OutboxStore>>) -> Self {
+        Self {
+            pending: Arc::new(RwLock::new(Vec::new())),
+            store,
+            loaded: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Load the pending events of the previous sessions, only once
+    async fn load(&self) {
+        self.loaded
+            .get_or_init(|| async {
+                let store: &Arc<dyn OutboxStore> = match &self.store {
+                    Some(store) => store,
+                    None => return,
+                };
+
+                match store.load().await {
+                    Ok(events) => {
+                        let mut pending = self.pending.write().await;
+                        for event in events.into_iter() {
+                            if !pending.iter().any(|p| p.id == event.id) {
+                                pending.push(event);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!(error = %e, "Failed to load the outbox events."),
+                }
+            })
+            .await;
+    }
+
+    /// Persist the pending events
+    async fn persist(&self, pending: &[Event]) -> Result<(), DatabaseError> {
+        match &self.store {
+            Some(store) => store.store(pending).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Mark event as pending
+    pub async fn add(&self, event: Event) -> Result<(), DatabaseError> {
+        self.load().await;
+
+        let mut pending = self.pending.write().await;
+        if !pending.iter().any(|p| p.id == event.id) {
+            pending.push(event);
+            self.persist(&pending).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove event from the pending list
+    pub async fn remove(&self, id: &EventId) -> Result<(), DatabaseError> {
+        self.load().await;
+
+        let mut pending = self.pending.write().await;
+        let len: usize = pending.len();
+        pending.retain(|p| &p.id != id);
+
+        if pending.len() != len {
+            self.persist(&pending).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Number of pending events
+    pub async fn len(&self) -> usize {
+        self.load().await;
+        self.pending.read().await.len()
+    }
+
+    /// Get pending events
+    pub async fn pending(&self) -> Vec<Event> {
+        self.load().await;
+        self.pending.read().await.clone()
+    }
+
+    /// Spawn a task that retry to send the pending events every time a relay connects
+    ///
+    /// The task exits when the pool is shutdown.
+    pub fn spawn_retry_task(&self, pool: RelayPool) {
+        let mut notifications = pool.notifications();
+        let outbox: Self = self.clone();
+
+        task::spawn(async move {
+            // Load the pending events of the previous sessions
+            outbox.load().await;
+
+            loop {
+                match notifications.recv().await {
+                    Ok(RelayPoolNotification::RelayStatus {
+                        relay_url,
+                        status: RelayStatus::Connected,
+                    }) => outbox.retry(&pool, relay_url).await,
+                    Ok(RelayPoolNotification::Shutdown) => break,
+                    Ok(..) => {}
+                    // Some notifications have been missed: retry with all the connected relays
+                    Err(RecvError::Lagged(..)) => {
+                        for (relay_url, relay) in pool.relays().await.into_iter() {
+                            if relay.is_connected() {
+                                outbox.retry(&pool, relay_url).await;
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            tracing::debug!("Exited from outbox retry task.");
+        });
+    }
+
+    async fn retry(&self, pool: &RelayPool, relay_url: RelayUrl) {
+        for event in self.pending().await.into_iter() {
+            match pool.send_event_to([relay_url.clone()], &event).await {
+                Ok(output) if !output.success.is_empty() => {
+                    if let Err(e) = self.remove(&event.id).await {
+                        tracing::warn!(id = %event.id, error = %e, "Failed to remove the event from the outbox.");
+                    }
+                }
+                Ok(..) => {}
+                Err(e) => {
+                    tracing::warn!(url = %relay_url, id = %event.id, error = %e, "Failed to re-send pending outbox event.")
+                }
+            }
+        }
+    }
+}
@@ -8,10 +8,8 @@ use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::hash::{Hash, Hasher};
 
-use secp256k1::schnorr::Signature;
-
 use super::tag::cow::CowTag;
-use crate::{Event, EventId, Kind, PublicKey, Tags, Timestamp};
+use crate::{Event, Kind, Tags, Timestamp};
 
 /// Borrowed event
 #[derive(Debug, Clone)]
@@ -67,15 +65,14 @@ impl Hash for EventBorrow<'_> {
 impl EventBorrow<'_> {
     /// Into owned event
     pub fn into_owned(self) -> Event {
-        Event::new(
-            EventId::from_byte_array(*self.id),
-            PublicKey::from_byte_array(*self.pubkey),
+        Event::from_raw_parts(
+            *self.id,
+            *self.pubkey,
             self.created_at,
             Kind::from_u16(self.kind),
             Tags::from_list(self.tags.into_iter().map(|t| t.into_owned()).collect()),
             self.content,
-            // SAFETY: signature panic only if it's not 64 byte long
-            Signature::from_slice(self.sig.as_slice()).expect("valid signature"),
+            *self.sig,
         )
     }
 }
@@ -206,8 +206,19 @@ impl EventBuilder {
     }
 
     /// Set a custom `created_at` UNIX timestamp
+    ///
+    /// Relays keep only the latest version of replaceable and addressable events:
+    /// if backdated, the event may be silently ignored.
+    /// With the `tracing` feature, a warning is logged if the timestamp is far in the future
+    /// or if a replaceable/addressable event is far in the past.
     #[inline]
     pub fn custom_created_at(mut self, created_at: Timestamp) -> Self {
+        #[cfg(all(feature = "std", feature = "tracing"))]
+        if let Some(warning) = custom_created_at_warning(&self.kind, &created_at, &Timestamp::now())
+        {
+            tracing::warn!(kind = %self.kind, created_at = %created_at, "{warning}");
+        }
+
         self.custom_created_at = Some(created_at);
         self
     }
@@ -239,6 +250,27 @@ impl EventBuilder {
         self
     }
 
+    /// Add a delegation tag
+    ///
+    /// The delegation is signed by the `delegator_keys` and authorizes the `delegatee` public key
+    /// (the one that will sign the event) to publish events that satisfy the `conditions`.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/26.md>
+    #[cfg(feature = "std")]
+    pub fn delegated(
+        self,
+        delegator_keys: &Keys,
+        delegatee: &PublicKey,
+        conditions: Conditions,
+    ) -> Self {
+        let tag: DelegationTag = DelegationTag::new(delegator_keys, delegatee, conditions);
+        self.tag(Tag::from_standardized(TagStandard::Delegation {
+            delegator: tag.delegator_pubkey(),
+            conditions: tag.conditions(),
+            sig: tag.signature(),
+        }))
+    }
+
     /// Build an unsigned event
     ///
     /// By default, this method removes any `p` tags that match the author's public key.
@@ -491,6 +523,40 @@ impl EventBuilder {
         Self::new(Kind::TextNote, content).tags(tags)
     }
 
+    /// Split a long text into a thread of text notes
+    ///
+    /// The text is split at word boundaries in chunks of at most `max_len` characters (at least 1).
+    /// A word longer than `max_len` is split in the middle.
+    /// Each chunk is a reply to the previous one, with the first chunk as root (see [`EventBuilder::text_note_reply`]).
+    ///
+    /// Return the events in thread order.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/10.md>
+    #[cfg(feature = "std")]
+    pub async fn thread_from_text<T>(
+        text: &str,
+        max_len: usize,
+        signer: &T,
+    ) -> Result<Vec<Event>, Error>
+    where
+        T: NostrSigner,
+    {
+        let chunks: Vec<&str> = split_text(text, max_len.max(1));
+        let mut events: Vec<Event> = Vec::with_capacity(chunks.len());
+
+        for chunk in chunks.into_iter() {
+            let builder: Self = match (events.first(), events.last()) {
+                (Some(root), Some(reply_to)) => {
+                    Self::text_note_reply(chunk, reply_to, Some(root), None)
+                }
+                _ => Self::text_note(chunk),
+            };
+            events.push(builder.sign(signer).await?);
+        }
+
+        Ok(events)
+    }
+
     /// Comment
     ///
     /// This adds only that most significant tags, like:
@@ -665,37 +731,37 @@ impl EventBuilder {
 
     /// Repost
     ///
+    /// Text notes are reposted with [`Kind::Repost`], all other kinds with [`Kind::GenericRepost`].
+    /// The reposted event is embedded as JSON in the content.
+    ///
     /// <https://github.com/nostr-protocol/nips/blob/master/18.md>
     pub fn repost(event: &Event, relay_url: Option<RelayUrl>) -> Self {
+        let mut tags: Vec<Tag> = Vec::with_capacity(4);
+
+        tags.push(Tag::from_standardized_without_cell(TagStandard::Event {
+            event_id: event.id,
+            relay_url: relay_url.clone(),
+            marker: None,
+            // NOTE: not add public key since it's already included as `p` tag
+            public_key: None,
+            uppercase: false,
+        }));
+        tags.push(Tag::public_key(event.pubkey));
+
         if event.kind == Kind::TextNote {
-            Self::new(Kind::Repost, event.as_json()).tags([
-                Tag::from_standardized_without_cell(TagStandard::Event {
-                    event_id: event.id,
-                    relay_url,
-                    marker: None,
-                    // NOTE: not add public key since it's already included as `p` tag
-                    public_key: None,
-                    uppercase: false,
-                }),
-                Tag::public_key(event.pubkey),
-            ])
-        } else {
-            Self::new(Kind::GenericRepost, event.as_json()).tags([
-                Tag::from_standardized_without_cell(TagStandard::Event {
-                    event_id: event.id,
-                    relay_url,
-                    marker: None,
-                    // NOTE: not add public key since it's already included as `p` tag
-                    public_key: None,
-                    uppercase: false,
-                }),
-                Tag::public_key(event.pubkey),
-                Tag::from_standardized_without_cell(TagStandard::Kind {
-                    kind: event.kind,
-                    uppercase: false,
-                }),
-            ])
+            return Self::new(Kind::Repost, event.as_json()).tags(tags);
         }
+
+        tags.push(Tag::from_standardized_without_cell(TagStandard::Kind {
+            kind: event.kind,
+            uppercase: false,
+        }));
+
+        if let Some(coordinate) = event.coordinate() {
+            tags.push(Tag::coordinate(coordinate.into_owned(), relay_url));
+        }
+
+        Self::new(Kind::GenericRepost, event.as_json()).tags(tags)
     }
 
     /// Event deletion request
@@ -743,17 +809,27 @@ impl EventBuilder {
 
     /// Add reaction (like/upvote, dislike/downvote or emoji) to an event
     ///
+    /// If the reaction is empty, the `+` (like/upvote) is used.
+    /// If the event is replaceable or addressable, the `a` tag is added too.
+    ///
     /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
-    #[inline]
     pub fn reaction<S>(event: &Event, reaction: S) -> Self
     where
         S: Into<String>,
     {
-        Self::reaction_extended(event.id, event.pubkey, Some(event.kind), reaction)
+        let builder: Self =
+            Self::reaction_extended(event.id, event.pubkey, Some(event.kind), reaction);
+
+        match event.coordinate() {
+            Some(coordinate) => builder.tag(Tag::coordinate(coordinate.into_owned(), None)),
+            None => builder,
+        }
     }
 
     /// Add reaction (like/upvote, dislike/downvote or emoji) to an event
     ///
+    /// If the reaction is empty, the `+` (like/upvote) is used.
+    ///
     /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
     pub fn reaction_extended<S>(
         event_id: EventId,
@@ -776,6 +852,11 @@ impl EventBuilder {
             }));
         }
 
+        let mut reaction: String = reaction.into();
+        if reaction.is_empty() {
+            reaction.push('+');
+        }
+
         Self::new(Kind::Reaction, reaction).tags(tags)
     }
 
@@ -1494,6 +1575,42 @@ impl EventBuilder {
         Self::gift_wrap(signer, &receiver, rumor, []).await
     }
 
+    /// List (i.e., mute list, bookmarks, pinned notes)
+    ///
+    /// The `public_items` are added as tags,
+    /// while the `private_items` are NIP-44 encrypted to the signer itself and put into the content.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/51.md>
+    pub async fn list<T>(
+        signer: &T,
+        kind: Kind,
+        public_items: Vec<Tag>,
+        private_items: Vec<Tag>,
+    ) -> Result<Self, Error>
+    where
+        T: NostrSigner,
+    {
+        let content: String = if private_items.is_empty() {
+            String::new()
+        } else {
+            let public_key: PublicKey = signer.get_public_key().await?;
+
+            // JSON array of tags
+            let items: Value = Value::Array(
+                private_items
+                    .iter()
+                    .map(|tag| json!(tag.as_slice()))
+                    .collect(),
+            );
+
+            signer
+                .nip44_encrypt(&public_key, &items.to_string())
+                .await?
+        };
+
+        Ok(Self::new(kind, content).tags(public_items))
+    }
+
     /// Mute list
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/51.md>
@@ -1789,6 +1906,67 @@ impl EventBuilder {
     }
 }
 
+/// Split the text at word boundaries in chunks of at most `max_len` characters
+#[cfg(feature = "std")]
+fn split_text(text: &str, max_len: usize) -> Vec<&str> {
+    let mut chunks: Vec<&str> = Vec::new();
+    let mut remaining: &str = text.trim();
+
+    while !remaining.is_empty() {
+        // Byte index of the first char over the limit
+        let limit: usize = match remaining.char_indices().nth(max_len) {
+            Some((index, ..)) => index,
+            None => {
+                chunks.push(remaining);
+                break;
+            }
+        };
+
+        // Split at the last whitespace within the limit (or at the limit, if the next char is a whitespace)
+        let split_at: usize = if remaining[limit..].starts_with(char::is_whitespace) {
+            limit
+        } else {
+            match remaining[..limit].rfind(char::is_whitespace) {
+                Some(index) if index > 0 => index,
+                // Word longer than the limit
+                _ => limit,
+            }
+        };
+
+        chunks.push(remaining[..split_at].trim_end());
+        remaining = remaining[split_at..].trim_start();
+    }
+
+    chunks
+}
+
+/// Get the warning for a custom `created_at`, if it's far in the future
+/// or if it's far in the past for a replaceable or addressable event.
+#[cfg(any(test, all(feature = "std", feature = "tracing")))]
+fn custom_created_at_warning(
+    kind: &Kind,
+    created_at: &Timestamp,
+    now: &Timestamp,
+) -> Option<&'static str> {
+    /// Max drift in the future before warning (15 min)
+    const MAX_FUTURE_DRIFT: u64 = 15 * 60;
+    /// Max drift in the past of replaceable and addressable events before warning (1 day)
+    const MAX_PAST_DRIFT: u64 = 24 * 60 * 60;
+
+    let now: u64 = now.as_u64();
+    let created_at: u64 = created_at.as_u64();
+
+    if created_at > now.saturating_add(MAX_FUTURE_DRIFT) {
+        Some("custom created_at is far in the future: the event may be rejected by relays.")
+    } else if (kind.is_replaceable() || kind.is_addressable())
+        && created_at.saturating_add(MAX_PAST_DRIFT) < now
+    {
+        Some("custom created_at is far in the past: the event may be ignored if a newer version exists.")
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "std")]
@@ -1816,6 +1994,64 @@ mod tests {
         assert_eq!(event, deserialized);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_custom_created_at() {
+        let keys = Keys::generate();
+
+        let created_at = Timestamp::from_secs(1_600_000_000);
+        let event = EventBuilder::text_note("Backdated")
+            .custom_created_at(created_at)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(event.created_at, created_at);
+
+        // Backdated replaceable event
+        let event = EventBuilder::metadata(&Metadata::new().name("Backdated"))
+            .custom_created_at(created_at)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(event.created_at, created_at);
+    }
+
+    #[test]
+    fn test_custom_created_at_warning() {
+        let now = Timestamp::from_secs(1_700_000_000);
+        let hour: u64 = 60 * 60;
+        let week: u64 = 7 * 24 * 60 * 60;
+
+        // Far in the future
+        assert!(custom_created_at_warning(&Kind::TextNote, &(now + hour), &now).is_some());
+
+        // Far in the past, only for replaceable and addressable events
+        assert!(custom_created_at_warning(&Kind::Metadata, &(now - week), &now).is_some());
+        assert!(custom_created_at_warning(&Kind::LongFormTextNote, &(now - week), &now).is_some());
+        assert!(custom_created_at_warning(&Kind::TextNote, &(now - week), &now).is_none());
+
+        // Near now
+        assert!(custom_created_at_warning(&Kind::Metadata, &now, &now).is_none());
+        assert!(custom_created_at_warning(&Kind::TextNote, &(now - hour), &now).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_delegated() {
+        let delegator_keys = Keys::generate();
+        let delegatee_keys = Keys::generate();
+        let conditions = Conditions::from_str("kind=1").unwrap();
+
+        let event = EventBuilder::text_note("Delegated")
+            .delegated(&delegator_keys, &delegatee_keys.public_key(), conditions)
+            .sign_with_keys(&delegatee_keys)
+            .unwrap();
+
+        assert_eq!(event.pubkey, delegatee_keys.public_key());
+        assert_eq!(
+            event.verify_delegation(),
+            Ok(Some(delegator_keys.public_key()))
+        );
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_self_tagging() {
@@ -2093,6 +2329,223 @@ mod tests {
         assert_eq!(ids.next().unwrap(), reply.id);
         assert_eq!(ids.next().unwrap(), root_event.id);
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_repost_text_note() {
+        let keys = Keys::generate();
+        let author = Keys::generate();
+        let target = EventBuilder::text_note("Hello")
+            .sign_with_keys(&author)
+            .unwrap();
+        let relay_url = RelayUrl::parse("wss://relay.damus.io").unwrap();
+
+        let repost = EventBuilder::repost(&target, Some(relay_url.clone()))
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(repost.kind, Kind::Repost);
+        assert_eq!(Event::from_json(&repost.content).unwrap(), target);
+        assert_eq!(
+            repost.tags.to_vec(),
+            vec![
+                Tag::from_standardized_without_cell(TagStandard::Event {
+                    event_id: target.id,
+                    relay_url: Some(relay_url),
+                    marker: None,
+                    public_key: None,
+                    uppercase: false,
+                }),
+                Tag::public_key(target.pubkey),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_repost_generic() {
+        let keys = Keys::generate();
+        let author = Keys::generate();
+        let target = EventBuilder::long_form_text_note("Article")
+            .tag(Tag::identifier("article"))
+            .sign_with_keys(&author)
+            .unwrap();
+
+        let repost = EventBuilder::repost(&target, None)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(repost.kind, Kind::GenericRepost);
+        assert_eq!(Event::from_json(&repost.content).unwrap(), target);
+        assert_eq!(
+            repost.tags.to_vec(),
+            vec![
+                Tag::event(target.id),
+                Tag::public_key(target.pubkey),
+                Tag::from_standardized_without_cell(TagStandard::Kind {
+                    kind: Kind::LongFormTextNote,
+                    uppercase: false,
+                }),
+                Tag::coordinate(
+                    Coordinate::new(Kind::LongFormTextNote, author.public_key())
+                        .identifier("article"),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_split_text() {
+        assert_eq!(
+            split_text("Lorem ipsum dolor sit amet", 11),
+            vec!["Lorem ipsum", "dolor sit", "amet"]
+        );
+        assert_eq!(
+            split_text("Loremipsum dolor", 5),
+            vec!["Lorem", "ipsum", "dolor"]
+        );
+        assert_eq!(split_text("  short  ", 100), vec!["short"]);
+        assert_eq!(split_text("ñññ ñññ", 3), vec!["ñññ", "ñññ"]);
+        assert!(split_text("", 10).is_empty());
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "std", feature = "nip44"))]
+    async fn test_list() {
+        let keys = Keys::generate();
+        let muted = Keys::generate().public_key();
+        let private_muted = Keys::generate().public_key();
+
+        let public_items: Vec<Tag> = vec![Tag::public_key(muted)];
+        let private_items: Vec<Tag> = vec![Tag::public_key(private_muted), Tag::hashtag("spam")];
+
+        let event: Event = EventBuilder::list(
+            &keys,
+            Kind::MuteList,
+            public_items.clone(),
+            private_items.clone(),
+        )
+        .await
+        .unwrap()
+        .sign_with_keys(&keys)
+        .unwrap();
+
+        assert_eq!(event.kind, Kind::MuteList);
+        assert_eq!(event.tags.clone().to_vec(), public_items);
+
+        // Decrypt private items
+        let json: String = keys
+            .nip44_decrypt(&keys.public_key(), &event.content)
+            .await
+            .unwrap();
+        let items: Vec<Vec<String>> = serde_json::from_str(&json).unwrap();
+        let items: Vec<Tag> = items.into_iter().map(|t| Tag::parse(t).unwrap()).collect();
+        assert_eq!(items, private_items);
+
+        // No private items
+        let event: Event = EventBuilder::list(&keys, Kind::Bookmarks, public_items, Vec::new())
+            .await
+            .unwrap()
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert!(event.content.is_empty());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "std")]
+    async fn test_thread_from_text() {
+        let keys = Keys::generate();
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(10);
+
+        let events = EventBuilder::thread_from_text(&text, 60, &keys)
+            .await
+            .unwrap();
+        assert!(events.len() > 1);
+
+        let root = &events[0];
+        assert!(root.tags.is_empty());
+
+        for (index, event) in events.iter().enumerate() {
+            assert!(event.verify().is_ok());
+            assert!(event.content.chars().count() <= 60);
+            assert!(!event.content.starts_with(' ') && !event.content.ends_with(' '));
+
+            if index == 0 {
+                continue;
+            }
+
+            let markers: Vec<(EventId, Option<Marker>)> = event
+                .tags
+                .iter()
+                .filter_map(|tag| match tag.as_standardized() {
+                    Some(TagStandard::Event {
+                        event_id, marker, ..
+                    }) => Some((*event_id, *marker)),
+                    _ => None,
+                })
+                .collect();
+
+            let parent = &events[index - 1];
+            if index == 1 {
+                // Reply to the root
+                assert_eq!(markers, vec![(root.id, Some(Marker::Root))]);
+            } else {
+                assert!(markers.contains(&(parent.id, Some(Marker::Reply))));
+                assert!(markers.contains(&(root.id, Some(Marker::Root))));
+                assert_eq!(markers.len(), 2);
+            }
+        }
+
+        // No content lost
+        let joined: Vec<&str> = events.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(joined.join(" "), text.trim());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_reaction() {
+        let keys = Keys::generate();
+        let author = Keys::generate();
+        let target = EventBuilder::text_note("Hello")
+            .sign_with_keys(&author)
+            .unwrap();
+
+        let reaction = EventBuilder::reaction(&target, "+")
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(reaction.kind, Kind::Reaction);
+        assert_eq!(reaction.content, "+");
+        assert_eq!(
+            reaction.tags.to_vec(),
+            vec![
+                Tag::event(target.id),
+                Tag::public_key(target.pubkey),
+                Tag::from_standardized_without_cell(TagStandard::Kind {
+                    kind: Kind::TextNote,
+                    uppercase: false,
+                }),
+            ]
+        );
+
+        // Default content
+        let reaction = EventBuilder::reaction(&target, "")
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(reaction.content, "+");
+
+        // Addressable target
+        let target = EventBuilder::long_form_text_note("Article")
+            .tag(Tag::identifier("article"))
+            .sign_with_keys(&author)
+            .unwrap();
+        let reaction = EventBuilder::reaction(&target, "-")
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(
+            reaction.tags.coordinates().next().unwrap(),
+            &Coordinate::new(Kind::LongFormTextNote, author.public_key()).identifier("article")
+        );
+    }
 }
 
 #[cfg(bench)]
@@ -2,9 +2,11 @@
 // Copyright (c) 2023-2025 Rust Nostr Developers
 // Distributed under the MIT software license
 
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use core::fmt;
 
+use super::tag;
 use crate::signer::SignerError;
 use crate::util::hex;
 
@@ -17,6 +19,8 @@ pub enum Error {
     Signer(String),
     /// Hex decode error
     Hex(hex::Error),
+    /// Tag error
+    Tag(Box<tag::Error>),
     /// Unknown JSON event key
     UnknownKey(String),
     /// Invalid event ID
@@ -34,6 +38,7 @@ impl fmt::Display for Error {
             Self::Json(e) => write!(f, "{e}"),
             Self::Signer(e) => write!(f, "{e}"),
             Self::Hex(e) => write!(f, "{e}"),
+            Self::Tag(e) => write!(f, "{e}"),
             Self::UnknownKey(key) => write!(f, "Unknown key: {key}"),
             Self::InvalidId => write!(f, "Invalid event ID"),
             Self::InvalidSignature => write!(f, "Invalid signature"),
@@ -58,3 +63,9 @@ impl From<hex::Error> for Error {
         Self::Hex(e)
     }
 }
+
+impl From<tag::Error> for Error {
+    fn from(e: tag::Error) -> Self {
+        Self::Tag(Box::new(e))
+    }
+}
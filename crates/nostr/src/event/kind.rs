@@ -27,6 +27,21 @@ pub const EPHEMERAL_RANGE: Range<u16> = 20_000..30_000;
 /// Addressable range
 pub const ADDRESSABLE_RANGE: Range<u16> = 30_000..40_000;
 
+/// Kind class
+///
+/// <https://github.com/nostr-protocol/nips/blob/master/01.md>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KindClass {
+    /// Regular: the event is expected to be stored by relays
+    Regular,
+    /// Replaceable: only the latest event for each combination of `pubkey` and `kind` is expected to be stored
+    Replaceable,
+    /// Ephemeral: the event is not expected to be stored by relays
+    Ephemeral,
+    /// Addressable: only the latest event for each combination of `pubkey`, `kind` and `d` tag is expected to be stored
+    Addressable,
+}
+
 macro_rules! kind_variants {
     ($($name:ident => $value:expr, $doc0:expr, $doc1:expr),* $(,)?) => {
         /// Event kind
@@ -65,6 +80,20 @@ macro_rules! kind_variants {
                 }
             }
         }
+
+        impl Kind {
+            /// Get the human-readable name of the kind
+            ///
+            /// Return `None` for the unknown kinds.
+            pub fn name(&self) -> Option<&'static str> {
+                match self.as_u16() {
+                    $(
+                        $value => Some($doc0),
+                    )*
+                    _ => None,
+                }
+            }
+        }
     };
 }
 
@@ -76,8 +105,8 @@ kind_variants! {
     OpenTimestamps => 1040, "OpenTimestamps Attestations", "<https://github.com/nostr-protocol/nips/blob/master/03.md>",
     EncryptedDirectMessage => 4, "Encrypted Direct Messages", "<https://github.com/nostr-protocol/nips/blob/master/04.md>",
     EventDeletion => 5, "Event Deletion", "<https://github.com/nostr-protocol/nips/blob/master/09.md>",
-    Repost => 6, "Repos", "<https://github.com/nostr-protocol/nips/blob/master/18.md>",
-    GenericRepost => 16, "Generic Repos", "<https://github.com/nostr-protocol/nips/blob/master/18.md>",
+    Repost => 6, "Repost", "<https://github.com/nostr-protocol/nips/blob/master/18.md>",
+    GenericRepost => 16, "Generic Repost", "<https://github.com/nostr-protocol/nips/blob/master/18.md>",
     Comment => 1111, "Comment", "<https://github.com/nostr-protocol/nips/blob/master/22.md>",
     Reaction => 7, "Reaction", "<https://github.com/nostr-protocol/nips/blob/master/25.md>",
     BadgeAward => 8, "Badge Award", "<https://github.com/nostr-protocol/nips/blob/master/58.md>",
@@ -104,9 +133,9 @@ kind_variants! {
     WalletConnectInfo => 13194, "Wallet Service Info", "<https://github.com/nostr-protocol/nips/blob/master/47.md>",
     Reporting => 1984, "Reporting", "<https://github.com/nostr-protocol/nips/blob/master/56.md>",
     Label => 1985, "Label", "<https://github.com/nostr-protocol/nips/blob/master/32.md>",
-    ZapPrivateMessage => 9733, "Zap Private Message ", "<https://github.com/nostr-protocol/nips/blob/master/57.md>",
-    ZapRequest => 9734, "Zap Request ", "<https://github.com/nostr-protocol/nips/blob/master/57.md>",
-    ZapReceipt => 9735, "Zap Receipt ", "<https://github.com/nostr-protocol/nips/blob/master/57.md>",
+    ZapPrivateMessage => 9733, "Zap Private Message", "<https://github.com/nostr-protocol/nips/blob/master/57.md>",
+    ZapRequest => 9734, "Zap Request", "<https://github.com/nostr-protocol/nips/blob/master/57.md>",
+    ZapReceipt => 9735, "Zap Receipt", "<https://github.com/nostr-protocol/nips/blob/master/57.md>",
     MuteList => 10000, "Mute List", "<https://github.com/nostr-protocol/nips/blob/master/51.md>",
     PinList => 10001, "Pin List", "<https://github.com/nostr-protocol/nips/blob/master/51.md>",
     Bookmarks => 10003, "Bookmarks", "<https://github.com/nostr-protocol/nips/blob/master/51.md>",
@@ -203,6 +232,21 @@ impl Kind {
         (*self).into()
     }
 
+    /// Get the kind class
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/01.md>
+    pub fn classification(&self) -> KindClass {
+        if self.is_replaceable() {
+            KindClass::Replaceable
+        } else if self.is_ephemeral() {
+            KindClass::Ephemeral
+        } else if self.is_addressable() {
+            KindClass::Addressable
+        } else {
+            KindClass::Regular
+        }
+    }
+
     /// Check if it's regular
     ///
     /// Regular means that event is expected to be stored by relays.
@@ -358,11 +402,50 @@ mod tests {
         assert_ne!(Kind::TextNote, Kind::Custom(2));
     }
 
+    #[test]
+    fn test_kind_name() {
+        assert_eq!(Kind::TextNote.name(), Some("Short Text Note"));
+        assert_eq!(Kind::Custom(1).name(), Some("Short Text Note"));
+        assert_eq!(Kind::ZapRequest.name(), Some("Zap Request"));
+        assert_eq!(Kind::Custom(45678).name(), None);
+    }
+
     #[test]
     fn test_kind_is_addressable() {
         assert!(Kind::Custom(32122).is_addressable());
         assert!(!Kind::TextNote.is_addressable());
     }
+
+    #[test]
+    fn test_kind_classification() {
+        assert_eq!(Kind::Metadata.classification(), KindClass::Replaceable);
+        assert_eq!(Kind::TextNote.classification(), KindClass::Regular);
+        assert_eq!(Kind::ContactList.classification(), KindClass::Replaceable);
+        assert_eq!(
+            Kind::ChannelMetadata.classification(),
+            KindClass::Replaceable
+        );
+        assert_eq!(Kind::Custom(9_999).classification(), KindClass::Regular);
+        assert_eq!(
+            Kind::Custom(10_000).classification(),
+            KindClass::Replaceable
+        );
+        assert_eq!(
+            Kind::Custom(19_999).classification(),
+            KindClass::Replaceable
+        );
+        assert_eq!(Kind::Custom(20_000).classification(), KindClass::Ephemeral);
+        assert_eq!(Kind::Custom(29_999).classification(), KindClass::Ephemeral);
+        assert_eq!(
+            Kind::Custom(30_000).classification(),
+            KindClass::Addressable
+        );
+        assert_eq!(
+            Kind::Custom(39_999).classification(),
+            KindClass::Addressable
+        );
+        assert_eq!(Kind::Custom(40_000).classification(), KindClass::Regular);
+    }
 }
 
 #[cfg(bench)]
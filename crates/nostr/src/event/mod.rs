@@ -7,6 +7,7 @@
 
 use alloc::borrow::Cow;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
@@ -27,16 +28,19 @@ pub mod unsigned;
 pub use self::builder::EventBuilder;
 pub use self::error::Error;
 pub use self::id::EventId;
-pub use self::kind::Kind;
+pub use self::kind::{Kind, KindClass};
 pub use self::tag::{Tag, TagKind, TagStandard, Tags};
 pub use self::unsigned::UnsignedEvent;
 use crate::nips::nip01::CoordinateBorrow;
+use crate::nips::nip02::{self, Contact};
+use crate::nips::nip26::{self, EventProperties, ValidationError};
+use crate::signer::NostrSigner;
 #[cfg(feature = "std")]
 use crate::types::time::Instant;
 use crate::types::time::TimeSupplier;
 #[cfg(feature = "std")]
 use crate::SECP256K1;
-use crate::{JsonUtil, Metadata, PublicKey, Timestamp};
+use crate::{JsonUtil, Metadata, PublicKey, RelayUrl, Timestamp};
 
 const ID: &str = "id";
 const PUBKEY: &str = "pubkey";
@@ -46,6 +50,21 @@ const TAGS: &str = "tags";
 const CONTENT: &str = "content";
 const SIG: &str = "sig";
 
+/// Key identifying a replaceable or addressable event
+///
+/// All the versions of the same replaceable or addressable event share the same key.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReplaceableKey {
+    /// Kind
+    pub kind: Kind,
+    /// Author
+    pub public_key: PublicKey,
+    /// `d` tag identifier
+    ///
+    /// Always `Some` for addressable events (empty if the `d` tag is missing) and `None` for replaceable events.
+    pub identifier: Option<String>,
+}
+
 /// Nostr event
 #[derive(Clone)]
 #[non_exhaustive] // In the future private fields may be added, so force event construction with `Event::new`.
@@ -152,6 +171,64 @@ impl Event {
         }
     }
 
+    /// Compose event from raw parts
+    ///
+    /// Useful to rebuild an event from the fixed-size byte arrays stored by database backends.
+    /// The ID and the signature are **not** verified.
+    pub fn from_raw_parts<I, S>(
+        id: [u8; EventId::LEN],
+        public_key: [u8; PublicKey::LEN],
+        created_at: Timestamp,
+        kind: Kind,
+        tags: I,
+        content: S,
+        sig: [u8; 64],
+    ) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+        S: Into<String>,
+    {
+        // SAFETY: the slice is 64 bytes long, the only check done by `Signature::from_slice`
+        let sig: Signature = Signature::from_slice(&sig).unwrap();
+
+        Self::new(
+            EventId::from_byte_array(id),
+            PublicKey::from_byte_array(public_key),
+            created_at,
+            kind,
+            tags,
+            content,
+            sig,
+        )
+    }
+
+    /// Convert to [`UnsignedEvent`]
+    ///
+    /// Drop the [`EventId`] and the [`Signature`], allowing to re-sign the event
+    /// (i.e., after changing the author or the tags).
+    #[inline]
+    pub fn to_unsigned(self) -> UnsignedEvent {
+        UnsignedEvent {
+            id: None,
+            pubkey: self.pubkey,
+            created_at: self.created_at,
+            kind: self.kind,
+            tags: self.tags,
+            content: self.content,
+        }
+    }
+
+    /// Check if two events have the same content, ignoring [`EventId`] and [`Signature`]
+    ///
+    /// Compare author, `created_at`, kind, tags and content.
+    pub fn content_eq(&self, other: &Event) -> bool {
+        self.pubkey == other.pubkey
+            && self.created_at == other.created_at
+            && self.kind == other.kind
+            && self.tags == other.tags
+            && self.content == other.content
+    }
+
     /// Verify both [`EventId`] and [`Signature`]
     #[inline]
     #[cfg(feature = "std")]
@@ -270,6 +347,29 @@ impl Event {
         None
     }
 
+    /// Get the key identifying this event and its other versions
+    ///
+    /// Return `None` if the event kind isn't `replaceable` or `addressable`.
+    pub fn replaceable_key(&self) -> Option<ReplaceableKey> {
+        if self.kind.is_replaceable() {
+            return Some(ReplaceableKey {
+                kind: self.kind,
+                public_key: self.pubkey,
+                identifier: None,
+            });
+        }
+
+        if self.kind.is_addressable() {
+            return Some(ReplaceableKey {
+                kind: self.kind,
+                public_key: self.pubkey,
+                identifier: Some(self.tags.identifier().unwrap_or_default().to_string()),
+            });
+        }
+
+        None
+    }
+
     /// Check if it's a protected event
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/70.md>
@@ -277,6 +377,164 @@ impl Event {
     pub fn is_protected(&self) -> bool {
         self.tags.find_standardized(TagKind::Protected).is_some()
     }
+
+    /// Decrypt the private items of a list
+    ///
+    /// The content is decrypted with NIP-44 or, if it has the legacy format, with NIP-04.
+    /// Return an empty list if the content is empty.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/51.md>
+    pub async fn decrypt_list_items<T>(&self, signer: &T) -> Result<Vec<Tag>, Error>
+    where
+        T: NostrSigner,
+    {
+        if self.content.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Private items are encrypted to the author itself
+        let json: String = if self.content.contains("?iv=") {
+            signer.nip04_decrypt(&self.pubkey, &self.content).await?
+        } else {
+            signer.nip44_decrypt(&self.pubkey, &self.content).await?
+        };
+
+        let items: Vec<Vec<String>> = serde_json::from_str(&json)?;
+        items
+            .into_iter()
+            .map(|item| Ok(Tag::parse(item)?))
+            .collect()
+    }
+
+    /// Extract the contacts from a contact list event
+    ///
+    /// Return an error if the event isn't a [`Kind::ContactList`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
+    pub fn contacts(&self) -> Result<Vec<Contact>, nip02::Error> {
+        if self.kind != Kind::ContactList {
+            return Err(nip02::Error::WrongKind {
+                received: self.kind,
+            });
+        }
+
+        Ok(self
+            .tags
+            .filter_standardized(TagKind::p())
+            .filter_map(|t| match t {
+                TagStandard::PublicKey {
+                    public_key,
+                    relay_url,
+                    alias,
+                    uppercase: false,
+                } => Some(Contact {
+                    public_key: *public_key,
+                    relay_url: relay_url.clone(),
+                    alias: alias.clone(),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Extract the relay URLs referenced by the tags
+    ///
+    /// Collect the relay hints of the `e`, `p`, `a` and `q` tags (and of their uppercase variants)
+    /// and the NIP65 `r` tags.
+    ///
+    /// Duplicated and invalid relay URLs are skipped.
+    pub fn relay_hints(&self) -> Vec<RelayUrl> {
+        let mut urls: Vec<RelayUrl> = Vec::new();
+
+        for tag in self.tags.iter() {
+            let tag: &[String] = tag.as_slice();
+
+            // Get the position of the relay URL
+            let index: usize = match tag.first().map(|k| k.as_str()) {
+                Some("e" | "p" | "a" | "q" | "E" | "P" | "A" | "Q") => 2,
+                Some("r") => 1,
+                _ => continue,
+            };
+
+            if let Some(Ok(url)) = tag.get(index).map(|url| RelayUrl::parse(url)) {
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+
+        urls
+    }
+
+    /// Get the quoted event, if any
+    ///
+    /// Return the event ID and the relay hint of the first `q` tag.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/18.md>
+    pub fn quoted_event(&self) -> Option<(EventId, Option<RelayUrl>)> {
+        self.tags
+            .iter()
+            .find_map(|tag| match tag.as_standardized() {
+                Some(TagStandard::Quote {
+                    event_id,
+                    relay_url,
+                    ..
+                }) => Some((*event_id, relay_url.clone())),
+                _ => None,
+            })
+    }
+
+    /// Verify the delegation tag, if any
+    ///
+    /// Check both the delegation signature and the conditions (kinds and `created_at` range).
+    ///
+    /// Return the delegator public key or `None` if the event hasn't a delegation tag.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/26.md>
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn verify_delegation(&self) -> Result<Option<PublicKey>, nip26::Error> {
+        self.verify_delegation_with_ctx(SECP256K1)
+    }
+
+    /// Verify the delegation tag, if any
+    ///
+    /// Check both the delegation signature and the conditions (kinds and `created_at` range).
+    ///
+    /// Return the delegator public key or `None` if the event hasn't a delegation tag.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/26.md>
+    pub fn verify_delegation_with_ctx<C>(
+        &self,
+        secp: &Secp256k1<C>,
+    ) -> Result<Option<PublicKey>, nip26::Error>
+    where
+        C: Verification,
+    {
+        match self.tags.find_standardized(TagKind::Delegation) {
+            Some(TagStandard::Delegation {
+                delegator,
+                conditions,
+                sig,
+            }) => {
+                // Verify signature
+                nip26::verify_delegation_signature_with_ctx(
+                    secp,
+                    delegator,
+                    *sig,
+                    &self.pubkey,
+                    conditions,
+                )
+                .map_err(|_| ValidationError::InvalidSignature)?;
+
+                // Validate conditions
+                conditions.evaluate(&EventProperties::from_event(self))?;
+
+                Ok(Some(*delegator))
+            }
+            _ => Ok(None),
+        }
+    }
 }
 
 impl JsonUtil for Event {
@@ -415,6 +673,304 @@ mod tests {
         assert_eq!(Kind::Custom(123), deserialized.kind);
     }
 
+    #[test]
+    fn test_contacts() {
+        let event = Event::from_json(r#"{"id":"0000000000000000000000000000000000000000000000000000000000000000","pubkey":"aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4","created_at":1704644581,"kind":3,"tags":[["p","79dff8f82963424e0bb02708a22e44b4980893e3a4be0fa3cb60a43b946764e3","wss://relay.damus.io","alice"],["p","68d81165918100b7da43fc28f7d1fc12554466e1115886b9e7bb326f65ec4272"],["p","1739d937dc8c0c7370aa27585938c119e25c41f6c441a5d34c6d38503e3136ef","","bob"],["t","nostr"]],"content":"","sig":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"}"#).unwrap();
+
+        let alice =
+            PublicKey::from_hex("79dff8f82963424e0bb02708a22e44b4980893e3a4be0fa3cb60a43b946764e3")
+                .unwrap();
+        let bare =
+            PublicKey::from_hex("68d81165918100b7da43fc28f7d1fc12554466e1115886b9e7bb326f65ec4272")
+                .unwrap();
+        let bob =
+            PublicKey::from_hex("1739d937dc8c0c7370aa27585938c119e25c41f6c441a5d34c6d38503e3136ef")
+                .unwrap();
+
+        assert_eq!(
+            event.contacts().unwrap(),
+            vec![
+                Contact {
+                    public_key: alice,
+                    relay_url: Some(RelayUrl::parse("wss://relay.damus.io").unwrap()),
+                    alias: Some(String::from("alice")),
+                },
+                Contact::new(bare),
+                Contact {
+                    public_key: bob,
+                    relay_url: None,
+                    alias: Some(String::from("bob")),
+                },
+            ]
+        );
+
+        // Not a contact list
+        let event = Event::from_json(r#"{"id":"0000000000000000000000000000000000000000000000000000000000000000","pubkey":"aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4","created_at":1704644581,"kind":1,"tags":[],"content":"","sig":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"}"#).unwrap();
+        assert_eq!(
+            event.contacts(),
+            Err(nip02::Error::WrongKind {
+                received: Kind::TextNote
+            })
+        );
+    }
+
+    #[test]
+    fn test_relay_hints() {
+        let event = Event::from_json(r#"{"id":"0000000000000000000000000000000000000000000000000000000000000000","pubkey":"aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4","created_at":1704644581,"kind":1,"tags":[["e","b7b1fb52ad8461a03e949820ae29a9ea07e35bcd79c95c4b59b0254944f62805","wss://relay.damus.io","root"],["p","79dff8f82963424e0bb02708a22e44b4980893e3a4be0fa3cb60a43b946764e3","wss://nos.lol"],["p","79dff8f82963424e0bb02708a22e44b4980893e3a4be0fa3cb60a43b946764e3","wss://relay.damus.io"],["p","aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4","not-a-url"],["r","https://example.com"],["t","wss://relay.example.com"]],"content":"","sig":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"}"#).unwrap();
+        assert_eq!(
+            event.relay_hints(),
+            vec![
+                RelayUrl::parse("wss://relay.damus.io").unwrap(),
+                RelayUrl::parse("wss://nos.lol").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quoted_event() {
+        let event = Event::from_json(r#"{"id":"0000000000000000000000000000000000000000000000000000000000000000","pubkey":"aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4","created_at":1704644581,"kind":1,"tags":[["p","79dff8f82963424e0bb02708a22e44b4980893e3a4be0fa3cb60a43b946764e3"],["q","b7b1fb52ad8461a03e949820ae29a9ea07e35bcd79c95c4b59b0254944f62805","wss://relay.damus.io"]],"content":"Quote nostr:note1k7cl754ds3s6q05jnqs2u2dfagr7xk7d08y4cj6ekqj5j38k9qzs6wd2z6","sig":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"}"#).unwrap();
+        assert_eq!(
+            event.quoted_event(),
+            Some((
+                EventId::from_hex(
+                    "b7b1fb52ad8461a03e949820ae29a9ea07e35bcd79c95c4b59b0254944f62805"
+                )
+                .unwrap(),
+                Some(RelayUrl::parse("wss://relay.damus.io").unwrap())
+            ))
+        );
+
+        let event = Event::from_json(r#"{"id":"0000000000000000000000000000000000000000000000000000000000000000","pubkey":"aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4","created_at":1704644581,"kind":1,"tags":[["e","b7b1fb52ad8461a03e949820ae29a9ea07e35bcd79c95c4b59b0254944f62805"]],"content":"Reply","sig":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"}"#).unwrap();
+        assert_eq!(event.quoted_event(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_unsigned() {
+        let keys = Keys::generate();
+        let event: Event = EventBuilder::text_note("Hello")
+            .tag(Tag::hashtag("nostr"))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // Re-sign with a different key
+        let new_keys = Keys::generate();
+        let mut unsigned: UnsignedEvent = event.clone().to_unsigned();
+        assert_eq!(unsigned.id, None);
+        unsigned.pubkey = new_keys.public_key();
+
+        let resigned: Event = unsigned.sign_with_keys(&new_keys).unwrap();
+        assert!(resigned.verify().is_ok());
+        assert_ne!(resigned.id, event.id);
+        assert_ne!(resigned.sig, event.sig);
+        assert_eq!(resigned.pubkey, new_keys.public_key());
+        assert_eq!(resigned.created_at, event.created_at);
+        assert_eq!(resigned.kind, event.kind);
+        assert_eq!(resigned.tags, event.tags);
+        assert_eq!(resigned.content, event.content);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_raw_parts() {
+        let keys = Keys::generate();
+        let event: Event = EventBuilder::text_note("Hello")
+            .tag(Tag::hashtag("nostr"))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let rebuilt = Event::from_raw_parts(
+            event.id.to_bytes(),
+            event.pubkey.to_bytes(),
+            event.created_at,
+            event.kind,
+            event.tags.clone(),
+            event.content.clone(),
+            event.sig.serialize(),
+        );
+        assert_eq!(rebuilt, event);
+        assert!(rebuilt.verify().is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "std", feature = "nip04", feature = "nip44"))]
+    async fn test_decrypt_list_items() {
+        let keys = Keys::generate();
+        let public_items: Vec<Tag> = vec![Tag::hashtag("nostr")];
+        let private_items: Vec<Tag> = vec![
+            Tag::public_key(Keys::generate().public_key()),
+            Tag::event(EventId::all_zeros()),
+        ];
+
+        let event: Event = EventBuilder::list(
+            &keys,
+            Kind::Bookmarks,
+            public_items.clone(),
+            private_items.clone(),
+        )
+        .await
+        .unwrap()
+        .sign_with_keys(&keys)
+        .unwrap();
+
+        assert_eq!(
+            event.decrypt_list_items(&keys).await.unwrap(),
+            private_items
+        );
+
+        // Legacy NIP-04 content
+        let content: String = keys
+            .nip04_encrypt(&keys.public_key(), r#"[["t","legacy"]]"#)
+            .await
+            .unwrap();
+        let event: Event = EventBuilder::new(Kind::MuteList, content)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(
+            event.decrypt_list_items(&keys).await.unwrap(),
+            vec![Tag::hashtag("legacy")]
+        );
+
+        // No private items
+        let event: Event = EventBuilder::list(&keys, Kind::Bookmarks, public_items, Vec::new())
+            .await
+            .unwrap()
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert!(event.decrypt_list_items(&keys).await.unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_replaceable_key() {
+        use std::collections::HashMap;
+
+        let keys = Keys::generate();
+
+        let v1: Event = EventBuilder::long_form_text_note("First version")
+            .tag(Tag::identifier("article"))
+            .custom_created_at(Timestamp::from_secs(1_000))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let v2: Event = EventBuilder::long_form_text_note("Second version")
+            .tag(Tag::identifier("article"))
+            .custom_created_at(Timestamp::from_secs(2_000))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let other: Event = EventBuilder::long_form_text_note("Other article")
+            .tag(Tag::identifier("other"))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let key: ReplaceableKey = v1.replaceable_key().unwrap();
+        assert_eq!(key.identifier.as_deref(), Some("article"));
+        assert_eq!(v2.replaceable_key(), Some(key.clone()));
+        assert_ne!(other.replaceable_key(), Some(key.clone()));
+
+        // Usable as map key
+        let mut latest: HashMap<ReplaceableKey, &Event> = HashMap::new();
+        for event in [&v1, &v2, &other] {
+            latest.insert(event.replaceable_key().unwrap(), event);
+        }
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest.get(&key), Some(&&v2));
+
+        // Replaceable
+        let metadata: Event = EventBuilder::metadata(&Metadata::new().name("yuki"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let key: ReplaceableKey = metadata.replaceable_key().unwrap();
+        assert_eq!(key.kind, Kind::Metadata);
+        assert_eq!(key.identifier, None);
+
+        // Regular
+        let note: Event = EventBuilder::text_note("Hello")
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(note.replaceable_key(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_content_eq() {
+        let keys = Keys::generate();
+        let unsigned: UnsignedEvent = EventBuilder::text_note("Hello").build(keys.public_key());
+
+        // Schnorr signatures use random aux data, so signing twice produces different signatures
+        let event1: Event = unsigned.clone().sign_with_keys(&keys).unwrap();
+        let event2: Event = unsigned.sign_with_keys(&keys).unwrap();
+        assert_ne!(event1.sig, event2.sig);
+
+        assert!(event1.content_eq(&event2));
+        assert_ne!(event1, event2);
+
+        let other: Event = EventBuilder::text_note("Hello, world!")
+            .custom_created_at(event1.created_at)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert!(!event1.content_eq(&other));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_verify_delegation() {
+        let delegator_keys = Keys::generate();
+        let delegatee_keys = Keys::generate();
+        let conditions =
+            nip26::Conditions::from_str("kind=1&created_at>1676067553&created_at<1678659553")
+                .unwrap();
+        let sig =
+            nip26::sign_delegation(&delegator_keys, &delegatee_keys.public_key(), &conditions);
+        let tag = Tag::from_standardized(TagStandard::Delegation {
+            delegator: delegator_keys.public_key(),
+            conditions,
+            sig,
+        });
+
+        // Valid
+        let event = EventBuilder::text_note("Delegated")
+            .tag(tag.clone())
+            .custom_created_at(Timestamp::from(1677000000))
+            .sign_with_keys(&delegatee_keys)
+            .unwrap();
+        assert_eq!(
+            event.verify_delegation(),
+            Ok(Some(delegator_keys.public_key()))
+        );
+
+        // Kind not allowed by conditions
+        let event = EventBuilder::new(Kind::Reaction, "+")
+            .tag(tag.clone())
+            .custom_created_at(Timestamp::from(1677000000))
+            .sign_with_keys(&delegatee_keys)
+            .unwrap();
+        assert_eq!(
+            event.verify_delegation(),
+            Err(nip26::Error::ConditionsValidation(
+                ValidationError::InvalidKind
+            ))
+        );
+
+        // Created after the validity period
+        let event = EventBuilder::text_note("Delegated")
+            .tag(tag)
+            .custom_created_at(Timestamp::from(1679000000))
+            .sign_with_keys(&delegatee_keys)
+            .unwrap();
+        assert_eq!(
+            event.verify_delegation(),
+            Err(nip26::Error::ConditionsValidation(
+                ValidationError::CreatedTooLate
+            ))
+        );
+
+        // No delegation
+        let event = EventBuilder::text_note("Not delegated")
+            .sign_with_keys(&delegatee_keys)
+            .unwrap();
+        assert_eq!(event.verify_delegation(), Ok(None));
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_event_expired() {
@@ -11,6 +11,8 @@ use core::fmt;
 use core::hash::Hash;
 use core::str::FromStr;
 
+use hashes::sha256::Hash as Sha256Hash;
+use hashes::Hash as _;
 use serde::de::{Deserializer, MapAccess, Visitor};
 use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
@@ -749,6 +751,39 @@ impl Filter {
         self == &Filter::default()
     }
 
+    /// Compute a stable hash of the canonical form of the filter
+    ///
+    /// Semantically-equal filters have the same hash, regardless of how they were built
+    /// (i.e., the order of the fields or of the values, an empty list of IDs, authors or kinds).
+    /// The hash is derived from the SHA256 of the canonical JSON,
+    /// so it's stable across platforms and releases: useful as a cache key.
+    pub fn canonical_hash(&self) -> u64 {
+        let mut filter: Self = self.clone();
+
+        // An empty list matches everything, like a missing one
+        if filter.ids.as_ref().is_some_and(|ids| ids.is_empty()) {
+            filter.ids = None;
+        }
+
+        if filter
+            .authors
+            .as_ref()
+            .is_some_and(|authors| authors.is_empty())
+        {
+            filter.authors = None;
+        }
+
+        if filter.kinds.as_ref().is_some_and(|kinds| kinds.is_empty()) {
+            filter.kinds = None;
+        }
+
+        // The lists are sorted sets, so the JSON is canonical
+        let hash: Sha256Hash = <Sha256Hash as hashes::Hash>::hash(filter.as_json().as_bytes());
+        let mut buf: [u8; 8] = [0u8; 8];
+        buf.copy_from_slice(&hash.as_byte_array()[..8]);
+        u64::from_be_bytes(buf)
+    }
+
     /// Extract **all** public keys (both from `authors` and `#p`)
     pub fn extract_public_keys(&self) -> BTreeSet<PublicKey> {
         let mut public_keys: BTreeSet<PublicKey> = BTreeSet::new();
@@ -1082,6 +1117,47 @@ mod tests {
         assert!(filter.is_empty());
     }
 
+    #[test]
+    fn test_canonical_hash() {
+        let pk_a =
+            PublicKey::from_hex("aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4")
+                .unwrap();
+        let pk_b =
+            PublicKey::from_hex("79dff8f82963424e0bb02708a22e44b4980893e3a4be0fa3cb60a43b946764e3")
+                .unwrap();
+
+        let a = Filter::new()
+            .kinds([Kind::TextNote, Kind::Metadata])
+            .authors([pk_a, pk_b])
+            .hashtag("nostr")
+            .since(Timestamp::from_secs(1_700_000_000))
+            .limit(10);
+        let b = Filter::new()
+            .limit(10)
+            .since(Timestamp::from_secs(1_700_000_000))
+            .hashtag("nostr")
+            .author(pk_b)
+            .kind(Kind::Metadata)
+            .author(pk_a)
+            .kind(Kind::TextNote)
+            .ids([]);
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+
+        let c = a.clone().limit(20);
+        assert_ne!(a.canonical_hash(), c.canonical_hash());
+
+        assert_eq!(
+            Filter::new().canonical_hash(),
+            Filter::new().kinds([]).authors([]).canonical_hash()
+        );
+
+        // The hash must be stable across releases
+        assert_eq!(
+            Filter::new().kind(Kind::TextNote).canonical_hash(),
+            11035330127883276451
+        );
+    }
+
     #[test]
     fn test_match_event() {
         let event_id =
@@ -5,16 +5,18 @@
 //! Public key
 
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
-use core::str::FromStr;
+use core::str::{self, FromStr};
 
 use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Deserializer, Serialize};
+use subtle::ConstantTimeEq;
 
 use super::Error;
-use crate::nips::nip19::FromBech32;
+use crate::nips::nip19::{self, FromBech32, PREFIX_BECH32_PUBLIC_KEY};
 use crate::nips::nip21::FromNostrUri;
 use crate::util::hex;
 
@@ -26,7 +28,7 @@ pub struct PublicKey {
 
 impl fmt::Debug for PublicKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "PublicKey({})", self.to_hex())
+        write!(f, "PublicKey({self})")
     }
 }
 
@@ -58,7 +60,9 @@ impl Hash for PublicKey {
 
 impl fmt::Display for PublicKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_hex())
+        let buf: [u8; PublicKey::LEN * 2] = self.encode_hex();
+        let hex: &str = str::from_utf8(&buf).map_err(|_| fmt::Error)?;
+        f.write_str(hex)
     }
 }
 
@@ -100,6 +104,46 @@ impl PublicKey {
         Err(Error::InvalidPublicKey)
     }
 
+    /// Parse from `npub` bech32, reporting the prefix found on mismatch
+    ///
+    /// Unlike [`FromBech32::from_bech32`], if the string uses another prefix (i.e. `nsec`),
+    /// return [`nip19::Error::UnexpectedPrefix`] with the found one.
+    pub fn from_bech32_checked(public_key: &str) -> Result<Self, nip19::Error> {
+        let (hrp, data) = bech32::decode(public_key)?;
+
+        let found: String = hrp.to_lowercase();
+        if found != PREFIX_BECH32_PUBLIC_KEY {
+            return Err(nip19::Error::UnexpectedPrefix {
+                expected: PREFIX_BECH32_PUBLIC_KEY,
+                found,
+            });
+        }
+
+        Ok(Self::from_slice(data.as_slice())?)
+    }
+
+    /// Parse many public keys from `hex`, `bech32` or [NIP21](https://github.com/nostr-protocol/nips/blob/master/21.md) uri
+    ///
+    /// A malformed entry doesn't abort the parsing:
+    /// return the parsed public keys and the errors, along with the index of the malformed entries.
+    pub fn parse_many<I, S>(public_keys: I) -> (Vec<Self>, Vec<(usize, Error)>)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut parsed: Vec<Self> = Vec::new();
+        let mut errors: Vec<(usize, Error)> = Vec::new();
+
+        for (index, public_key) in public_keys.into_iter().enumerate() {
+            match Self::parse(public_key.as_ref()) {
+                Ok(public_key) => parsed.push(public_key),
+                Err(e) => errors.push((index, e)),
+            }
+        }
+
+        (parsed, errors)
+    }
+
     /// Parse from hex string
     pub fn from_hex(hex: &str) -> Result<Self, Error> {
         let mut bytes: [u8; Self::LEN] = [0u8; Self::LEN];
@@ -128,6 +172,17 @@ impl PublicKey {
         hex::encode(self.as_bytes())
     }
 
+    /// Encode as `hex` into a stack buffer
+    ///
+    /// Used by the [`fmt::Display`] and [`Serialize`] impls to avoid allocating a [`String`].
+    #[inline]
+    fn encode_hex(&self) -> [u8; Self::LEN * 2] {
+        let mut buf: [u8; Self::LEN * 2] = [0u8; Self::LEN * 2];
+        // SAFETY: the buffer len is the double of the public key len
+        hex::encode_to_slice(self.as_bytes(), &mut buf).expect("Valid buffer len");
+        buf
+    }
+
     /// Get as bytes
     #[inline]
     pub fn as_bytes(&self) -> &[u8; Self::LEN] {
@@ -140,9 +195,20 @@ impl PublicKey {
         self.buf
     }
 
+    /// Constant-time equality
+    ///
+    /// Unlike [`PartialEq`], the comparison time doesn't depend on the content of the public keys:
+    /// use it to avoid leaking timing info (i.e., when checking a signer against an allowlist).
+    #[inline]
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        self.buf.as_slice().ct_eq(other.buf.as_slice()).into()
+    }
+
     /// Get the x-only public key
+    ///
+    /// The x-only public key is computed on every call and never cached:
+    /// this keeps [`PublicKey`] in its minimal 32-byte form.
     pub fn xonly(&self) -> Result<XOnlyPublicKey, Error> {
-        // TODO: use a OnceCell
         Ok(XOnlyPublicKey::from_slice(self.as_bytes())?)
     }
 }
@@ -169,7 +235,9 @@ impl Serialize for PublicKey {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        let buf: [u8; Self::LEN * 2] = self.encode_hex();
+        let hex: &str = str::from_utf8(&buf).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(hex)
     }
 }
 
@@ -185,7 +253,14 @@ impl<'de> Deserialize<'de> for PublicKey {
 
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString;
+
+    #[cfg(feature = "std")]
+    use secp256k1::Message;
+
     use super::*;
+    #[cfg(feature = "std")]
+    use crate::{EventBuilder, Keys, SECP256K1};
 
     #[test]
     fn test_public_key_parse() {
@@ -199,6 +274,75 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_public_key_parse_many() {
+        let hex_pk: &str = "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4";
+        let (public_keys, errors) = PublicKey::parse_many([
+            hex_pk,
+            "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+            "not-a-public-key",
+            "nostr:npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+        ]);
+
+        let expected = PublicKey::from_hex(hex_pk).unwrap();
+        assert_eq!(public_keys, vec![expected; 3]);
+        assert_eq!(errors, vec![(2, Error::InvalidPublicKey)]);
+    }
+
+    #[test]
+    fn test_public_key_from_bech32_checked() {
+        let public_key = PublicKey::from_bech32_checked(
+            "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+        )
+        .unwrap();
+        assert_eq!(
+            public_key.to_hex(),
+            "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4"
+        );
+
+        let err = PublicKey::from_bech32_checked(
+            "nsec1j4c6269y9w0q2er2xjw8sv2ehyrtfxq3jwgdlxj6qfn8z4gjsq5qfvfk99",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            nip19::Error::UnexpectedPrefix {
+                expected: "npub",
+                found: String::from("nsec"),
+            }
+        );
+        assert!(err.to_string().contains("nsec"));
+    }
+
+    #[test]
+    fn test_public_key_display_serialize() {
+        let hex_pk: &str = "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4";
+        let public_key = PublicKey::from_hex(hex_pk).unwrap();
+
+        assert_eq!(public_key.to_string(), hex_pk);
+        assert_eq!(public_key.to_string(), public_key.to_hex());
+        assert_eq!(
+            serde_json::to_string(&public_key).unwrap(),
+            format!("\"{hex_pk}\"")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_ct_eq() {
+        let public_keys: Vec<PublicKey> = (0..10).map(|_| Keys::generate().public_key()).collect();
+
+        for a in public_keys.iter() {
+            for b in public_keys.iter() {
+                assert_eq!(a.ct_eq(b), a == b);
+            }
+
+            let copy = PublicKey::from_byte_array(a.to_bytes());
+            assert!(a.ct_eq(&copy));
+        }
+    }
+
     #[test]
     fn test_as_xonly() {
         let hex_pk: &str = "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4";
@@ -215,6 +359,25 @@ mod tests {
         let xonly = public_key.xonly().unwrap();
         assert_eq!(&xonly, &expected);
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_minimal_form() {
+        assert_eq!(core::mem::size_of::<PublicKey>(), PublicKey::LEN);
+
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Minimal form")
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        // Rebuild the public key from raw bytes and verify the signature
+        let public_key = PublicKey::from_byte_array(keys.public_key().to_bytes());
+        let message = Message::from_digest(event.id.to_bytes());
+        let xonly = public_key.xonly().unwrap();
+        SECP256K1
+            .verify_schnorr(&event.sig, &message, &xonly)
+            .unwrap();
+    }
 }
 
 #[cfg(bench)]
@@ -267,7 +430,15 @@ mod benches {
     pub fn public_key_to_hex(bh: &mut Bencher) {
         let public_key = PublicKey::from_hex(HEX).unwrap();
         bh.iter(|| {
-            black_box(public_key.to_bech32()).unwrap();
+            black_box(public_key.to_hex());
+        });
+    }
+
+    #[bench]
+    pub fn public_key_encode_hex(bh: &mut Bencher) {
+        let public_key = PublicKey::from_hex(HEX).unwrap();
+        bh.iter(|| {
+            black_box(public_key.encode_hex());
         });
     }
 
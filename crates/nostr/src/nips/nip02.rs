@@ -7,9 +7,36 @@
 //! <https://github.com/nostr-protocol/nips/blob/master/02.md>
 
 use alloc::string::String;
+use core::fmt;
 
 use crate::key::PublicKey;
 use crate::types::RelayUrl;
+use crate::Kind;
+
+/// NIP02 error
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The event isn't a contact list
+    WrongKind {
+        /// The received kind
+        received: Kind,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongKind { received } => write!(
+                f,
+                "Wrong kind: received={received}, expected={}",
+                Kind::ContactList
+            ),
+        }
+    }
+}
 
 /// Contact
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -83,6 +83,53 @@ pub struct Nip05Profile {
     pub nip46: Vec<RelayUrl>,
 }
 
+/// Check if a NIP05 identifier is well-formed (`<local-part>@<domain>`)
+///
+/// The `local-part` must be composed only of `a-z0-9-_.` characters (case-insensitive)
+/// and the `domain` must be a valid DNS name.
+///
+/// This function doesn't do any network request: use [`verify`] to verify the identifier.
+pub fn is_valid_format(nip05: &str) -> bool {
+    let (name, domain) = match nip05.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    is_valid_name(name) && is_valid_domain(domain)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    // Max length of a DNS name
+    if domain.is_empty() || domain.len() > 253 {
+        return false;
+    }
+
+    let mut labels: usize = 0;
+
+    for label in domain.split('.') {
+        if label.is_empty()
+            || label.len() > 63
+            || label.starts_with('-')
+            || label.ends_with('-')
+            || !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return false;
+        }
+
+        labels += 1;
+    }
+
+    // At least the domain name and the TLD
+    labels >= 2
+}
+
 fn compose_url(nip05: &str) -> Result<(String, &str), Error> {
     let mut split = nip05.split('@');
     if let (Some(name), Some(domain)) = (split.next(), split.next()) {
@@ -220,4 +267,28 @@ mod tests {
                 .unwrap();
         assert!(!verify_from_json(&public_key, &json, "yuki"));
     }
+
+    #[test]
+    fn test_is_valid_format() {
+        assert!(is_valid_format("_@yukikishimoto.com"));
+        assert!(is_valid_format("yuki@yukikishimoto.com"));
+        assert!(is_valid_format("Yuki.K-1_a@sub.example-domain.org"));
+
+        // Missing `@`
+        assert!(!is_valid_format("yukikishimoto.com"));
+        assert!(!is_valid_format(""));
+
+        // Invalid name
+        assert!(!is_valid_format("@yukikishimoto.com"));
+        assert!(!is_valid_format("yu ki@yukikishimoto.com"));
+        assert!(!is_valid_format("yuki@yuki@yukikishimoto.com"));
+
+        // Invalid domain
+        assert!(!is_valid_format("yuki@"));
+        assert!(!is_valid_format("yuki@localhost"));
+        assert!(!is_valid_format("yuki@example..com"));
+        assert!(!is_valid_format("yuki@-example.com"));
+        assert!(!is_valid_format("yuki@example.com/path"));
+        assert!(!is_valid_format("yuki@exa_mple.com"));
+    }
 }
@@ -230,6 +230,14 @@ impl RelayInformationDocument {
         Self::default()
     }
 
+    /// Check if the relay advertises the support of a NIP in the `supported_nips` field
+    pub fn supports_nip(&self, nip: u16) -> bool {
+        match &self.supported_nips {
+            Some(nips) => nips.contains(&nip),
+            None => false,
+        }
+    }
+
     /// Get Relay Information Document
     pub async fn get(mut url: Url, opts: Nip11GetOptions) -> Result<Self, Error> {
         let mut builder = Client::builder();
@@ -304,4 +312,16 @@ mod tests {
 
         assert_eq!(got, expected, "got: {:?}, expected: {:?}", got, expected);
     }
+
+    #[test]
+    fn test_supports_nip() {
+        let json = r#"{"name":"Test relay","supported_nips":[1,45]}"#;
+        let document: RelayInformationDocument = serde_json::from_str(json).unwrap();
+        assert!(document.supports_nip(1));
+        assert!(document.supports_nip(45));
+        assert!(!document.supports_nip(50));
+
+        let document = RelayInformationDocument::new();
+        assert!(!document.supports_nip(1));
+    }
 }
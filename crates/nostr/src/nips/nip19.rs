@@ -25,7 +25,7 @@ use super::nip05::Nip05Profile;
 use super::nip49::{self, EncryptedSecretKey};
 use crate::event::id::EventId;
 use crate::types::url::{self, RelayUrl};
-use crate::{event, key, Event, Kind, PublicKey, SecretKey};
+use crate::{event, key, Event, Filter, Kind, PublicKey, SecretKey};
 
 pub const PREFIX_BECH32_SECRET_KEY: &str = "nsec";
 pub const PREFIX_BECH32_SECRET_KEY_ENCRYPTED: &str = "ncryptsec";
@@ -73,6 +73,13 @@ pub enum Error {
     NIP49(nip49::Error),
     /// Wrong prefix or variant
     WrongPrefix,
+    /// Unexpected prefix
+    UnexpectedPrefix {
+        /// Expected prefix
+        expected: &'static str,
+        /// Found prefix
+        found: String,
+    },
     /// Field missing
     FieldMissing(String),
     /// TLV error
@@ -95,6 +102,9 @@ impl fmt::Display for Error {
             #[cfg(feature = "nip49")]
             Self::NIP49(e) => write!(f, "{e}"),
             Self::WrongPrefix => write!(f, "Wrong prefix"),
+            Self::UnexpectedPrefix { expected, found } => {
+                write!(f, "Unexpected prefix: expected {expected}, found {found}")
+            }
             Self::FieldMissing(name) => write!(f, "Field missing: {name}"),
             Self::TLV => write!(f, "TLV error"),
             Self::TryFromSlice => write!(f, "From slice error"),
@@ -482,6 +492,28 @@ impl Nip19Event {
     }
 }
 
+impl From<Nip19Event> for Filter {
+    fn from(value: Nip19Event) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&Nip19Event> for Filter {
+    fn from(value: &Nip19Event) -> Self {
+        let mut filter: Filter = Filter::new().id(value.event_id);
+
+        if let Some(author) = value.author {
+            filter = filter.author(author);
+        }
+
+        if let Some(kind) = value.kind {
+            filter = filter.kind(kind);
+        }
+
+        filter
+    }
+}
+
 impl FromBech32 for Nip19Event {
     type Err = Error;
 
@@ -739,6 +771,18 @@ impl Nip19Coordinate {
     }
 }
 
+impl From<Nip19Coordinate> for Filter {
+    fn from(value: Nip19Coordinate) -> Self {
+        Self::from(value.coordinate)
+    }
+}
+
+impl From<&Nip19Coordinate> for Filter {
+    fn from(value: &Nip19Coordinate) -> Self {
+        Self::from(&value.coordinate)
+    }
+}
+
 impl FromBech32 for Nip19Coordinate {
     type Err = Error;
 
@@ -907,6 +951,50 @@ mod tests {
         assert_eq!(coordinate.identifier, exected_identifier);
     }
 
+    #[test]
+    fn test_nevent_to_filter() {
+        let nevent = "nevent1qqsdhet4232flykq3048jzc9msmaa3hnxuesxy3lnc33vd0wt9xwk6szyqewrqnkx4zsaweutf739s0cu7et29zrntqs5elw70vlm8zudr3y24sqsgy";
+        let nip19_event = Nip19Event::from_bech32(nevent).unwrap();
+
+        let filter: Filter = Filter::from(&nip19_event);
+        assert_eq!(
+            filter,
+            Filter::new()
+                .id(nip19_event.event_id)
+                .author(nip19_event.author.unwrap())
+        );
+
+        let nip19_event = nip19_event.kind(Kind::TextNote);
+        let filter: Filter = Filter::from(nip19_event.clone());
+        assert_eq!(
+            filter,
+            Filter::new()
+                .id(nip19_event.event_id)
+                .author(nip19_event.author.unwrap())
+                .kind(Kind::TextNote)
+        );
+    }
+
+    #[test]
+    fn test_naddr_to_filter() {
+        let naddr: &str = "naddr1qqxnzd3exgersv33xymnsve3qgs8suecw4luyht9ekff89x4uacneapk8r5dyk0gmn6uwwurf6u9rusrqsqqqa282m3gxt";
+        let coordinate = Nip19Coordinate::from_bech32(naddr).unwrap();
+
+        let filter: Filter = Filter::from(coordinate);
+        assert_eq!(
+            filter,
+            Filter::new()
+                .kind(Kind::LongFormTextNote)
+                .author(
+                    PublicKey::from_hex(
+                        "787338757fc25d65cd929394d5e7713cf43638e8d259e8dcf5c73b834eb851f2"
+                    )
+                    .unwrap()
+                )
+                .identifier("1692282117831")
+        );
+    }
+
     #[test]
     fn test_parse_nevent_with_malformed_public_key() {
         let event = Nip19Event::from_bech32("nevent1qqsqye53g5jg5pzw87q6a3nstkf2wu7jph87nala2nvfyw5u3ewlhfspr9mhxue69uhkymmnw3ezumr9vd682unfveujumn9wspyqve5xasnyvehxqunqvryxyukydr9xsmn2d3jxgcn2wf5v5uxyerpxucrvct9x43nwwp4v3jnqwt9x5uk2dpkxq6kvwf3vycrxe35893ska2ytu").unwrap();
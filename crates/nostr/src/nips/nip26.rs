@@ -466,7 +466,7 @@ impl Conditions {
     }
 
     /// Evaluate whether an event satisfies all these conditions
-    fn evaluate(&self, ep: &EventProperties) -> Result<(), ValidationError> {
+    pub(crate) fn evaluate(&self, ep: &EventProperties) -> Result<(), ValidationError> {
         for c in &self.0 {
             c.evaluate(ep)?;
         }
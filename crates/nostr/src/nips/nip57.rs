@@ -32,7 +32,7 @@ use crate::types::time::TimeSupplier;
 use crate::SECP256K1;
 use crate::{
     event, util, Event, EventBuilder, EventId, JsonUtil, Keys, Kind, PublicKey, RelayUrl,
-    SecretKey, Tag, TagStandard, Timestamp,
+    SecretKey, Tag, TagStandard, Timestamp, Url,
 };
 
 type Aes256CbcEnc = Encryptor<Aes256>;
@@ -40,6 +40,7 @@ type Aes256CbcDec = Decryptor<Aes256>;
 
 const PRIVATE_ZAP_MSG_BECH32_PREFIX: Hrp = Hrp::parse_unchecked("pzap");
 const PRIVATE_ZAP_IV_BECH32_PREFIX: Hrp = Hrp::parse_unchecked("iv");
+const LNURL_BECH32_PREFIX: Hrp = Hrp::parse_unchecked("lnurl");
 
 #[allow(missing_docs)]
 #[derive(Debug)]
@@ -49,12 +50,17 @@ pub enum Error {
     Event(event::Error),
     Bech32Decode(bech32::DecodeError),
     Bech32Encode(bech32::EncodeError),
+    Json(serde_json::Error),
     InvalidPrivateZapMessage,
     PrivateZapMessageNotFound,
     /// Wrong prefix or variant
     WrongBech32Prefix,
     /// Wrong encryption block mode
     WrongBlockMode,
+    /// Invalid lightning address
+    InvalidLightningAddress,
+    /// Invalid LNURL
+    InvalidLnurl,
 }
 
 #[cfg(feature = "std")]
@@ -68,6 +74,7 @@ impl fmt::Display for Error {
             Self::Event(e) => write!(f, "{e}"),
             Self::Bech32Decode(e) => write!(f, "{e}"),
             Self::Bech32Encode(e) => write!(f, "{e}"),
+            Self::Json(e) => write!(f, "{e}"),
             Self::InvalidPrivateZapMessage => write!(f, "Invalid private zap message"),
             Self::PrivateZapMessageNotFound => write!(f, "Private zap message not found"),
             Self::WrongBech32Prefix => write!(f, "Wrong bech32 prefix"),
@@ -75,6 +82,8 @@ impl fmt::Display for Error {
                 f,
                 "Wrong encryption block mode. The content must be encrypted using CBC mode!"
             ),
+            Self::InvalidLightningAddress => write!(f, "Invalid lightning address"),
+            Self::InvalidLnurl => write!(f, "Invalid LNURL"),
         }
     }
 }
@@ -109,6 +118,104 @@ impl From<bech32::EncodeError> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// LNURL-pay response
+///
+/// <https://github.com/lnurl/luds/blob/luds/06.md>
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LnUrlPayResponse {
+    /// URL to call to request the invoice
+    pub callback: Url,
+    /// Min amount, in `millisats`
+    #[serde(rename = "minSendable")]
+    pub min_sendable: u64,
+    /// Max amount, in `millisats`
+    #[serde(rename = "maxSendable")]
+    pub max_sendable: u64,
+    /// Metadata JSON, as string
+    pub metadata: String,
+    /// Whether the zap requests are allowed
+    #[serde(rename = "allowsNostr", default)]
+    pub allows_nostr: bool,
+    /// Public key used to sign the zap receipts
+    #[serde(
+        rename = "nostrPubkey",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub nostr_pubkey: Option<PublicKey>,
+}
+
+impl LnUrlPayResponse {
+    /// Check if the recipient supports nostr zaps
+    #[inline]
+    pub fn supports_zaps(&self) -> bool {
+        self.allows_nostr && self.nostr_pubkey.is_some()
+    }
+}
+
+impl JsonUtil for LnUrlPayResponse {
+    type Err = Error;
+}
+
+/// Get the LNURL-pay endpoint of a lightning address (`user@domain`)
+///
+/// <https://github.com/lnurl/luds/blob/luds/16.md>
+pub fn lightning_address_to_url(address: &str) -> Result<Url, Error> {
+    let (user, domain) = address
+        .trim()
+        .split_once('@')
+        .ok_or(Error::InvalidLightningAddress)?;
+
+    if user.is_empty()
+        || domain.is_empty()
+        || !user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+    {
+        return Err(Error::InvalidLightningAddress);
+    }
+
+    // Onion services must use plain HTTP
+    let scheme: &str = if domain.ends_with(".onion") {
+        "http"
+    } else {
+        "https"
+    };
+
+    Url::parse(&format!("{scheme}://{domain}/.well-known/lnurlp/{user}"))
+        .map_err(|_| Error::InvalidLightningAddress)
+}
+
+/// Decode a bech32 LNURL (`lnurl1...`)
+///
+/// <https://github.com/lnurl/luds/blob/luds/01.md>
+pub fn lnurl_to_url(lnurl: &str) -> Result<Url, Error> {
+    let (hrp, data) = bech32::decode(lnurl.trim())?;
+
+    if hrp.to_lowercase() != LNURL_BECH32_PREFIX.to_lowercase() {
+        return Err(Error::WrongBech32Prefix);
+    }
+
+    let url: &str = core::str::from_utf8(&data).map_err(|_| Error::InvalidLnurl)?;
+    Url::parse(url).map_err(|_| Error::InvalidLnurl)
+}
+
+/// Encode an URL as bech32 LNURL (`lnurl1...`)
+///
+/// <https://github.com/lnurl/luds/blob/luds/01.md>
+pub fn url_to_lnurl(url: &Url) -> Result<String, Error> {
+    Ok(bech32::encode::<Bech32>(
+        LNURL_BECH32_PREFIX,
+        url.as_str().as_bytes(),
+    )?)
+}
+
 /// Zap Type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ZapType {
@@ -420,6 +527,50 @@ fn decrypt_private_zap_message(key: [u8; 32], private_zap_event: &Event) -> Resu
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_lightning_address_to_url() {
+        assert_eq!(
+            lightning_address_to_url("pay@yukikishimoto.com").unwrap(),
+            Url::parse("https://yukikishimoto.com/.well-known/lnurlp/pay").unwrap()
+        );
+        assert_eq!(
+            lightning_address_to_url("user@example.onion").unwrap(),
+            Url::parse("http://example.onion/.well-known/lnurlp/user").unwrap()
+        );
+        assert!(lightning_address_to_url("yukikishimoto.com").is_err());
+        assert!(lightning_address_to_url("@yukikishimoto.com").is_err());
+        assert!(lightning_address_to_url("pay@").is_err());
+        assert!(lightning_address_to_url("p ay@yukikishimoto.com").is_err());
+    }
+
+    #[test]
+    fn test_lnurl() {
+        let lnurl = "LNURL1DP68GURN8GHJ7UM9WFMXJCM99E3K7MF0V9CXJ0M385EKVCENXC6R2C35XVUKXEFCV5MKVV34X5EKZD3EV56NYD3HXQURZEPEXEJXXEPNXSCRVWFNV9NXZCN9XQ6XYEFHVGCXXCMYXYMNSERXFQ5FNS";
+        let url = lnurl_to_url(lnurl).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df"
+        );
+        assert_eq!(url_to_lnurl(&url).unwrap(), lnurl.to_lowercase());
+
+        let npub = "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy";
+        assert!(matches!(lnurl_to_url(npub), Err(Error::WrongBech32Prefix)));
+    }
+
+    #[test]
+    fn test_lnurl_pay_response() {
+        let json = r#"{"callback":"https://example.com/lnurlp/pay/callback","maxSendable":100000000,"minSendable":1000,"metadata":"[[\"text/plain\",\"Pay\"]]","tag":"payRequest","allowsNostr":true,"nostrPubkey":"be1d89794bf92de5dd64c1e60f6a2c70c140abac9932418fee30c5c637fe9479"}"#;
+        let res = LnUrlPayResponse::from_json(json).unwrap();
+        assert_eq!(res.min_sendable, 1_000);
+        assert_eq!(res.max_sendable, 100_000_000);
+        assert!(res.supports_zaps());
+
+        let json = r#"{"callback":"https://example.com/lnurlp/pay/callback","maxSendable":100000000,"minSendable":1000,"metadata":"[]","tag":"payRequest"}"#;
+        let res = LnUrlPayResponse::from_json(json).unwrap();
+        assert!(!res.allows_nostr);
+        assert!(!res.supports_zaps());
+    }
+
     #[test]
     fn test_encrypt_decrypt_private_zap_message() {
         let alice_keys = Keys::generate();
@@ -0,0 +1,86 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2025 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Clock
+
+use core::fmt;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use super::supplier::{Instant, SystemTime, UNIX_EPOCH};
+use super::{TimeSupplier, Timestamp};
+
+/// Clock
+///
+/// Object-safe view of a [`TimeSupplier`], used where the source of time must be stored (i.e. `Arc<dyn Clock>`)
+/// by time-dependent logic (i.e. NIP-40 expiration checks).
+///
+/// Every [`TimeSupplier`] is automatically a [`Clock`].
+/// Simple clocks (i.e. the controllable ones used in tests) can implement this trait directly.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Get the current UNIX timestamp
+    fn now(&self) -> Timestamp;
+}
+
+impl<T> Clock for T
+where
+    T: TimeSupplier + fmt::Debug + Send + Sync,
+{
+    #[inline]
+    fn now(&self) -> Timestamp {
+        Timestamp::now_with_supplier(self)
+    }
+}
+
+/// System clock
+///
+/// Default [`Clock`], which reads the current time from the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg(feature = "std")]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl TimeSupplier for SystemClock {
+    type Now = Instant;
+    type StartingPoint = SystemTime;
+
+    fn now(&self) -> Self::StartingPoint {
+        SystemTime::now()
+    }
+
+    fn instant_now(&self) -> Self::Now {
+        Instant::now()
+    }
+
+    fn starting_point(&self) -> Self::StartingPoint {
+        UNIX_EPOCH
+    }
+
+    fn duration_since_starting_point(&self, now: Self::StartingPoint) -> Duration {
+        now.duration_since(self.starting_point())
+            .unwrap_or_default()
+    }
+
+    fn elapsed_instant_since(&self, now: Self::Now, since: Self::Now) -> Duration {
+        now - since
+    }
+
+    fn elapsed_since(&self, now: Self::StartingPoint, since: Self::StartingPoint) -> Duration {
+        now.duration_since(since).unwrap_or_default()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock() {
+        let clock: &dyn Clock = &SystemClock;
+        let now: Timestamp = Timestamp::now();
+        assert!(clock.now() >= now);
+        assert!(clock.now() <= now + Duration::from_secs(1));
+    }
+}
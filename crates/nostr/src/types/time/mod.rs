@@ -14,8 +14,12 @@ use core::time::Duration;
 use secp256k1::rand::rngs::OsRng;
 use secp256k1::rand::Rng;
 
+mod clock;
 mod supplier;
 
+pub use self::clock::Clock;
+#[cfg(feature = "std")]
+pub use self::clock::SystemClock;
 pub use self::supplier::TimeSupplier;
 #[cfg(feature = "std")]
 pub use self::supplier::{Instant, SystemTime, UNIX_EPOCH};
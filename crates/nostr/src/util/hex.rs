@@ -63,6 +63,28 @@ where
     hex
 }
 
+/// Hex encode to slice
+///
+/// The `out` len must be the double of the `data` len.
+#[inline]
+pub fn encode_to_slice<T>(data: T, out: &mut [u8]) -> Result<(), Error>
+where
+    T: AsRef<[u8]>,
+{
+    let data: &[u8] = data.as_ref();
+
+    if data.len() * 2 != out.len() {
+        return Err(Error::InvalidLength);
+    }
+
+    for (byte, chunk) in data.iter().zip(out.chunks_exact_mut(2)) {
+        chunk[0] = from_digit(byte >> 4);
+        chunk[1] = from_digit(byte & 0xF);
+    }
+
+    Ok(())
+}
+
 /// Hex decode
 #[inline]
 pub fn decode<T>(hex: T) -> Result<Vec<u8>, Error>
@@ -129,6 +151,19 @@ mod tests {
         assert_eq!(encode(b"foobar"), "666f6f626172");
     }
 
+    #[test]
+    fn test_encode_to_slice() {
+        let mut out = [0u8; 12];
+        encode_to_slice(b"foobar", &mut out).unwrap();
+        assert_eq!(&out, b"666f6f626172");
+
+        let mut out = [0u8; 10];
+        assert_eq!(
+            encode_to_slice(b"foobar", &mut out),
+            Err(Error::InvalidLength)
+        );
+    }
+
     #[test]
     fn test_decode() {
         assert_eq!(